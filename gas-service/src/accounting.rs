@@ -0,0 +1,126 @@
+use crate::constants::GasToken;
+
+multiversx_sc::imports!();
+
+// Cumulative totals of gas paid, refunded and collected, so the balance of the contract can be reconciled on chain.
+// Payments made with the add gas endpoints have no destination chain, so they are only counted in the per token totals
+#[multiversx_sc::module]
+pub trait AccountingModule {
+    fn add_gas_paid(
+        &self,
+        payment_id: u64,
+        destination_chain: Option<&ManagedBuffer>,
+        tokens: &ManagedVec<GasToken<Self::Api>>,
+    ) {
+        for token in tokens.iter() {
+            self.total_gas_paid(&token.token)
+                .update(|total| *total += &token.amount);
+
+            if let Some(destination_chain) = destination_chain {
+                self.total_gas_paid_for_chain(destination_chain, &token.token)
+                    .update(|total| *total += &token.amount);
+            }
+        }
+
+        if let Some(destination_chain) = destination_chain {
+            self.payment_destination_chain(payment_id)
+                .set(destination_chain);
+        }
+    }
+
+    fn add_gas_refunded(
+        &self,
+        payment_id: u64,
+        tokens: &ManagedVec<GasToken<Self::Api>>,
+        refund_amounts: &ManagedVec<BigUint>,
+    ) {
+        let destination_chain_mapper = self.payment_destination_chain(payment_id);
+        let destination_chain = if destination_chain_mapper.is_empty() {
+            None
+        } else {
+            Some(destination_chain_mapper.get())
+        };
+
+        for (token, refund_amount) in tokens.iter().zip(refund_amounts.iter()) {
+            if *refund_amount == 0 {
+                continue;
+            }
+
+            self.total_gas_refunded(&token.token)
+                .update(|total| *total += &*refund_amount);
+
+            if let Some(destination_chain) = &destination_chain {
+                self.total_gas_refunded_for_chain(destination_chain, &token.token)
+                    .update(|total| *total += &*refund_amount);
+            }
+        }
+    }
+
+    fn add_fees_collected(&self, token: &EgldOrEsdtTokenIdentifier, amount: &BigUint) {
+        self.total_fees_collected(token)
+            .update(|total| *total += amount);
+    }
+
+    fn add_express_reimbursed(&self, tokens: &ManagedVec<GasToken<Self::Api>>) {
+        for token in tokens.iter() {
+            self.total_express_reimbursed(&token.token)
+                .update(|total| *total += &token.amount);
+        }
+    }
+
+    // Gas which was paid and is still held by the contract, ie. neither refunded, reimbursed to express relayers nor collected.
+    // Totals only start being tracked once the contract is upgraded, so this is 0 if more was paid out than recorded as paid
+    #[view(outstandingBalance)]
+    fn outstanding_balance(&self, token: &EgldOrEsdtTokenIdentifier) -> BigUint {
+        let paid = self.total_gas_paid(token).get();
+        let paid_out = self.total_gas_refunded(token).get()
+            + self.total_express_reimbursed(token).get()
+            + self.total_fees_collected(token).get();
+
+        if paid <= paid_out {
+            return BigUint::zero();
+        }
+
+        paid - paid_out
+    }
+
+    #[view(totalGasPaid)]
+    #[storage_mapper("total_gas_paid")]
+    fn total_gas_paid(&self, token: &EgldOrEsdtTokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    #[view(totalGasPaidForChain)]
+    #[storage_mapper("total_gas_paid_for_chain")]
+    fn total_gas_paid_for_chain(
+        &self,
+        destination_chain: &ManagedBuffer,
+        token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(totalGasRefunded)]
+    #[storage_mapper("total_gas_refunded")]
+    fn total_gas_refunded(&self, token: &EgldOrEsdtTokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    #[view(totalGasRefundedForChain)]
+    #[storage_mapper("total_gas_refunded_for_chain")]
+    fn total_gas_refunded_for_chain(
+        &self,
+        destination_chain: &ManagedBuffer,
+        token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(totalFeesCollected)]
+    #[storage_mapper("total_fees_collected")]
+    fn total_fees_collected(&self, token: &EgldOrEsdtTokenIdentifier)
+        -> SingleValueMapper<BigUint>;
+
+    #[view(totalExpressReimbursed)]
+    #[storage_mapper("total_express_reimbursed")]
+    fn total_express_reimbursed(
+        &self,
+        token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(paymentDestinationChain)]
+    #[storage_mapper("payment_destination_chain")]
+    fn payment_destination_chain(&self, payment_id: u64) -> SingleValueMapper<ManagedBuffer>;
+}
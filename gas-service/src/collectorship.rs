@@ -0,0 +1,113 @@
+multiversx_sc::imports!();
+
+use operatable::roles::Roles;
+
+// Fees are collected by addresses with the COLLECTOR role, while payments are refunded by addresses with the
+// REFUNDER role. Both roles can be held by several addresses, managed by the owner or handed over by their holders.
+// Express calls can only be registered by addresses with the EXPRESS_RELAYER role, managed by the owner
+#[multiversx_sc::module]
+pub trait Collectorship: operatable::roles::AccountRoles {
+    #[only_owner]
+    #[endpoint(addCollector)]
+    fn add_collector(&self, collector: ManagedAddress) {
+        self.add_role(collector, Roles::COLLECTOR);
+    }
+
+    #[only_owner]
+    #[endpoint(removeCollector)]
+    fn remove_collector(&self, collector: ManagedAddress) {
+        self.remove_role(collector, Roles::COLLECTOR);
+    }
+
+    #[endpoint(proposeCollectorship)]
+    fn propose_collectorship(&self, collector: ManagedAddress) {
+        self.only_collector();
+
+        self.propose_role(self.blockchain().get_caller(), collector, Roles::COLLECTOR);
+    }
+
+    #[endpoint(acceptCollectorship)]
+    fn accept_collectorship(&self, from_collector: ManagedAddress) {
+        self.accept_role(
+            from_collector,
+            self.blockchain().get_caller(),
+            Roles::COLLECTOR,
+        );
+    }
+
+    #[only_owner]
+    #[endpoint(addRefunder)]
+    fn add_refunder(&self, refunder: ManagedAddress) {
+        self.add_role(refunder, Roles::REFUNDER);
+    }
+
+    #[only_owner]
+    #[endpoint(removeRefunder)]
+    fn remove_refunder(&self, refunder: ManagedAddress) {
+        self.remove_role(refunder, Roles::REFUNDER);
+    }
+
+    #[endpoint(proposeRefundership)]
+    fn propose_refundership(&self, refunder: ManagedAddress) {
+        self.only_refunder();
+
+        self.propose_role(self.blockchain().get_caller(), refunder, Roles::REFUNDER);
+    }
+
+    #[endpoint(acceptRefundership)]
+    fn accept_refundership(&self, from_refunder: ManagedAddress) {
+        self.accept_role(
+            from_refunder,
+            self.blockchain().get_caller(),
+            Roles::REFUNDER,
+        );
+    }
+
+    #[only_owner]
+    #[endpoint(addExpressRelayer)]
+    fn add_express_relayer(&self, express_relayer: ManagedAddress) {
+        self.add_role(express_relayer, Roles::EXPRESS_RELAYER);
+    }
+
+    #[only_owner]
+    #[endpoint(removeExpressRelayer)]
+    fn remove_express_relayer(&self, express_relayer: ManagedAddress) {
+        self.remove_role(express_relayer, Roles::EXPRESS_RELAYER);
+    }
+
+    fn only_collector(&self) {
+        let caller = self.blockchain().get_caller();
+
+        require!(self.has_role(&caller, Roles::COLLECTOR), "Not collector");
+    }
+
+    fn only_refunder(&self) {
+        let caller = self.blockchain().get_caller();
+
+        require!(self.has_role(&caller, Roles::REFUNDER), "Not refunder");
+    }
+
+    fn only_express_relayer(&self) {
+        let caller = self.blockchain().get_caller();
+
+        require!(
+            self.has_role(&caller, Roles::EXPRESS_RELAYER),
+            "Not express relayer"
+        );
+    }
+
+    #[view(isCollector)]
+    fn is_collector(&self, address: &ManagedAddress) -> bool {
+        self.has_role(address, Roles::COLLECTOR)
+    }
+
+    #[view(isRefunder)]
+    fn is_refunder(&self, address: &ManagedAddress) -> bool {
+        self.has_role(address, Roles::REFUNDER)
+    }
+
+    #[view(isExpressRelayer)]
+    fn is_express_relayer(&self, address: &ManagedAddress) -> bool {
+        self.has_role(address, Roles::EXPRESS_RELAYER)
+    }
+}
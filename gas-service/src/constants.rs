@@ -0,0 +1,77 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+// EGLD sent as part of a multi ESDT transfer
+pub const ESDT_EGLD_IDENTIFIER: &str = "EGLD-000000";
+
+// Shares of the fee distribution table are in basis points and need to add up to this
+pub const TOTAL_SHARES_BPS: u32 = 10_000;
+
+// Amount paid in one token as part of a gas payment
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, ManagedVecItem, Clone)]
+pub struct GasToken<M: ManagedTypeApi> {
+    pub token: EgldOrEsdtTokenIdentifier<M>,
+    pub amount: BigUint<M>,
+}
+
+// Gas paid by a single call to one of the pay or add gas endpoints, in one or more tokens. Once settled, the
+// unspent part of each token was refunded and the rest of the payment can no longer be refunded
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub struct GasPayment<M: ManagedTypeApi> {
+    pub tokens: ManagedVec<M, GasToken<M>>,
+    pub refund_address: ManagedAddress<M>,
+    // Refunded amount of each token, in the same order as `tokens`
+    pub refunded: ManagedVec<M, BigUint<M>>,
+    pub settled: bool,
+    // Express gas is escrowed until it is either used to reimburse an express relayer or refunded
+    pub express: bool,
+}
+
+// Minimum gas fee quoted for a destination chain and token: base_fee + fee_per_byte * payload length
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub struct GasFee<M: ManagedTypeApi> {
+    pub base_fee: BigUint<M>,
+    pub fee_per_byte: BigUint<M>,
+}
+
+// Outcome of a single entry of the batch refund and fee collection endpoints. Entries which can't be processed
+// are skipped instead of failing the whole batch
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, Copy, PartialEq)]
+pub enum BatchItemResult {
+    Success,
+    InvalidPayment,
+    PaymentAlreadySettled,
+    InvalidAmounts,
+    RefundExceedsPayment,
+    InsufficientBalance,
+    ExpressCallPending,
+}
+
+impl BatchItemResult {
+    pub fn error_message(&self) -> &'static str {
+        match self {
+            BatchItemResult::Success => "",
+            BatchItemResult::InvalidPayment => "Invalid payment",
+            BatchItemResult::PaymentAlreadySettled => "Payment already settled",
+            BatchItemResult::InvalidAmounts => "Invalid amounts",
+            BatchItemResult::RefundExceedsPayment => "Refund exceeds payment",
+            BatchItemResult::InsufficientBalance => "Insufficient balance",
+            BatchItemResult::ExpressCallPending => "Express call pending",
+        }
+    }
+}
+
+// Execution of a command fronted by an express relayer on the destination chain, backed by an express payment
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub struct ExpressCall<M: ManagedTypeApi> {
+    pub payment_id: u64,
+    pub relayer: ManagedAddress<M>,
+    pub reimbursed: bool,
+}
+
+// Receiver of a share of the fees distributed by the distributeFees endpoint, in basis points
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, ManagedVecItem, Clone)]
+pub struct FeeReceiver<M: ManagedTypeApi> {
+    pub receiver: ManagedAddress<M>,
+    pub share_bps: u32,
+}
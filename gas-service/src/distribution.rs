@@ -0,0 +1,112 @@
+use crate::accounting;
+use crate::collectorship;
+use crate::constants::{FeeReceiver, TOTAL_SHARES_BPS};
+use crate::events;
+use crate::payments;
+
+multiversx_sc::imports!();
+
+// Splits collected fees across a table of receivers managed by the owner (governance), each receiving
+// a share of every token in basis points
+#[multiversx_sc::module]
+pub trait DistributionModule:
+    events::Events
+    + accounting::AccountingModule
+    + payments::PaymentsModule
+    + collectorship::Collectorship
+    + operatable::roles::AccountRoles
+{
+    // Replaces the whole distribution table, shares needing to add up to 100%. An empty table disables distribution
+    #[only_owner]
+    #[endpoint(setFeeReceivers)]
+    fn set_fee_receivers(
+        &self,
+        fee_receivers: MultiValueEncoded<MultiValue2<ManagedAddress, u32>>,
+    ) {
+        let mut table = ManagedVec::<Self::Api, FeeReceiver<Self::Api>>::new();
+        let mut total_shares_bps = 0u32;
+
+        for fee_receiver in fee_receivers.into_iter() {
+            let (receiver, share_bps) = fee_receiver.into_tuple();
+
+            require!(!receiver.is_zero(), "Invalid address");
+            require!(share_bps > 0, "Invalid shares");
+            require!(
+                !table.iter().any(|existing| existing.receiver == receiver),
+                "Duplicate receiver"
+            );
+
+            require!(
+                share_bps <= TOTAL_SHARES_BPS - total_shares_bps,
+                "Invalid shares"
+            );
+
+            total_shares_bps += share_bps;
+
+            table.push(FeeReceiver {
+                receiver,
+                share_bps,
+            });
+        }
+
+        require!(
+            table.is_empty() || total_shares_bps == TOTAL_SHARES_BPS,
+            "Invalid shares"
+        );
+
+        self.fee_receivers_set_event(&table);
+
+        self.fee_receivers().set(table);
+    }
+
+    // Distributes the collectable balance of each token. Shares are rounded down and the rounding remainder
+    // goes to the first receiver of the table, so the whole balance is always distributed
+    #[endpoint(distributeFees)]
+    fn distribute_fees(&self, tokens: MultiValueEncoded<EgldOrEsdtTokenIdentifier>) {
+        self.only_collector();
+
+        let fee_receivers = self.fee_receivers().get();
+
+        require!(!fee_receivers.is_empty(), "No fee receivers");
+
+        for token in tokens.into_iter() {
+            let amount = self.available_balance(&token);
+
+            if amount == 0 {
+                continue;
+            }
+
+            self.add_fees_collected(&token, &amount);
+
+            let mut remainder = amount.clone();
+            for fee_receiver in fee_receivers.iter() {
+                remainder -= self.fee_share(&amount, fee_receiver.share_bps);
+            }
+
+            for (index, fee_receiver) in fee_receivers.iter().enumerate() {
+                let mut payout = self.fee_share(&amount, fee_receiver.share_bps);
+
+                if index == 0 {
+                    payout += &remainder;
+                }
+
+                if payout == 0 {
+                    continue;
+                }
+
+                self.send()
+                    .direct(&fee_receiver.receiver, &token, 0, &payout);
+
+                self.fees_distributed_event(&fee_receiver.receiver, &token, &payout);
+            }
+        }
+    }
+
+    fn fee_share(&self, amount: &BigUint, share_bps: u32) -> BigUint {
+        amount * share_bps / TOTAL_SHARES_BPS
+    }
+
+    #[view(feeReceivers)]
+    #[storage_mapper("fee_receivers")]
+    fn fee_receivers(&self) -> SingleValueMapper<ManagedVec<FeeReceiver<Self::Api>>>;
+}
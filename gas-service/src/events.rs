@@ -3,6 +3,8 @@ multiversx_sc::derive_imports!();
 
 use multiversx_sc::api::KECCAK256_RESULT_LEN;
 
+use crate::constants::{BatchItemResult, FeeReceiver, GasToken};
+
 #[derive(TypeAbi, TopEncode)]
 pub struct GasPaidForContractCallData<M: ManagedTypeApi> {
     pub hash: ManagedByteArray<M, KECCAK256_RESULT_LEN>,
@@ -31,6 +33,19 @@ pub struct AddNativeGasData<M: ManagedTypeApi> {
     pub refund_address: ManagedAddress<M>,
 }
 
+#[derive(TypeAbi, TopEncode)]
+pub struct MultiTokenGasPaidForContractCallData<M: ManagedTypeApi> {
+    pub hash: ManagedByteArray<M, KECCAK256_RESULT_LEN>,
+    pub payments: ManagedVec<M, GasToken<M>>,
+    pub refund_address: ManagedAddress<M>,
+}
+
+#[derive(TypeAbi, TopEncode)]
+pub struct AddMultiTokenGasData<M: ManagedTypeApi> {
+    pub payments: ManagedVec<M, GasToken<M>>,
+    pub refund_address: ManagedAddress<M>,
+}
+
 #[derive(TypeAbi, TopEncode)]
 pub struct RefundedData<M: ManagedTypeApi> {
     pub receiver: ManagedAddress<M>,
@@ -46,6 +61,7 @@ pub trait Events {
         #[indexed] sender: ManagedAddress,
         #[indexed] destination_chain: ManagedBuffer,
         #[indexed] destination_contract_address: ManagedBuffer,
+        #[indexed] payment_id: u64,
         data: GasPaidForContractCallData<Self::Api>,
     );
 
@@ -55,6 +71,7 @@ pub trait Events {
         #[indexed] sender: ManagedAddress,
         #[indexed] destination_chain: ManagedBuffer,
         #[indexed] destination_contract_address: ManagedBuffer,
+        #[indexed] payment_id: u64,
         data: NativeGasPaidForContractCallData<Self::Api>,
     );
 
@@ -64,6 +81,7 @@ pub trait Events {
         #[indexed] sender: ManagedAddress,
         #[indexed] destination_chain: ManagedBuffer,
         #[indexed] destination_contract_address: ManagedBuffer,
+        #[indexed] payment_id: u64,
         data: GasPaidForContractCallData<Self::Api>,
     );
 
@@ -73,6 +91,7 @@ pub trait Events {
         #[indexed] sender: ManagedAddress,
         #[indexed] destination_chain: ManagedBuffer,
         #[indexed] destination_contract_address: ManagedBuffer,
+        #[indexed] payment_id: u64,
         data: NativeGasPaidForContractCallData<Self::Api>,
     );
 
@@ -81,6 +100,7 @@ pub trait Events {
         &self,
         #[indexed] tx_hash: ManagedBuffer,
         #[indexed] log_index: BigUint,
+        #[indexed] payment_id: u64,
         data: AddGasData<Self::Api>,
     );
 
@@ -89,6 +109,7 @@ pub trait Events {
         &self,
         #[indexed] tx_hash: ManagedBuffer,
         #[indexed] log_index: BigUint,
+        #[indexed] payment_id: u64,
         data: AddNativeGasData<Self::Api>,
     );
 
@@ -97,6 +118,7 @@ pub trait Events {
         &self,
         #[indexed] tx_hash: ManagedBuffer,
         #[indexed] log_index: BigUint,
+        #[indexed] payment_id: u64,
         data: AddGasData<Self::Api>,
     );
 
@@ -105,9 +127,123 @@ pub trait Events {
         &self,
         #[indexed] tx_hash: ManagedBuffer,
         #[indexed] log_index: BigUint,
+        #[indexed] payment_id: u64,
         data: AddNativeGasData<Self::Api>,
     );
 
+    #[event("multi_token_gas_paid_for_contract_call_event")]
+    fn multi_token_gas_paid_for_contract_call_event(
+        &self,
+        #[indexed] sender: ManagedAddress,
+        #[indexed] destination_chain: ManagedBuffer,
+        #[indexed] destination_contract_address: ManagedBuffer,
+        #[indexed] payment_id: u64,
+        data: MultiTokenGasPaidForContractCallData<Self::Api>,
+    );
+
+    #[event("multi_token_gas_added_event")]
+    fn multi_token_gas_added_event(
+        &self,
+        #[indexed] tx_hash: ManagedBuffer,
+        #[indexed] log_index: BigUint,
+        #[indexed] payment_id: u64,
+        data: AddMultiTokenGasData<Self::Api>,
+    );
+
+    #[event("gas_payment_recorded_event")]
+    fn gas_payment_recorded_event(
+        &self,
+        #[indexed] payment_id: u64,
+        #[indexed] refund_address: &ManagedAddress,
+        tokens: &ManagedVec<GasToken<Self::Api>>,
+    );
+
+    #[event("gas_payment_settled_event")]
+    fn gas_payment_settled_event(&self, #[indexed] payment_id: u64, refunded: &ManagedVec<BigUint>);
+
+    #[event("express_call_registered_event")]
+    fn express_call_registered_event(
+        &self,
+        #[indexed] command_id: &ManagedByteArray<KECCAK256_RESULT_LEN>,
+        #[indexed] payment_id: u64,
+        #[indexed] relayer: &ManagedAddress,
+    );
+
+    #[event("express_call_reimbursed_event")]
+    fn express_call_reimbursed_event(
+        &self,
+        #[indexed] command_id: &ManagedByteArray<KECCAK256_RESULT_LEN>,
+        #[indexed] payment_id: u64,
+        #[indexed] relayer: &ManagedAddress,
+        tokens: &ManagedVec<GasToken<Self::Api>>,
+    );
+
+    #[event("express_call_cancelled_event")]
+    fn express_call_cancelled_event(
+        &self,
+        #[indexed] command_id: &ManagedByteArray<KECCAK256_RESULT_LEN>,
+        #[indexed] payment_id: u64,
+    );
+
+    #[event("gas_fee_set_event")]
+    fn gas_fee_set_event(
+        &self,
+        #[indexed] destination_chain: &ManagedBuffer,
+        #[indexed] token: &EgldOrEsdtTokenIdentifier,
+        #[indexed] base_fee: &BigUint,
+        fee_per_byte: &BigUint,
+    );
+
+    #[event("gas_fee_removed_event")]
+    fn gas_fee_removed_event(
+        &self,
+        #[indexed] destination_chain: &ManagedBuffer,
+        #[indexed] token: &EgldOrEsdtTokenIdentifier,
+    );
+
+    #[event("min_gas_fee_enforced_set_event")]
+    fn min_gas_fee_enforced_set_event(&self, #[indexed] enforced: bool);
+
+    #[event("refund_result_event")]
+    fn refund_result_event(
+        &self,
+        #[indexed] tx_hash: &ManagedBuffer,
+        #[indexed] log_index: &BigUint,
+        #[indexed] payment_id: u64,
+        result: BatchItemResult,
+    );
+
+    #[event("collect_fees_result_event")]
+    fn collect_fees_result_event(
+        &self,
+        #[indexed] receiver: &ManagedAddress,
+        #[indexed] token: &EgldOrEsdtTokenIdentifier,
+        #[indexed] amount: &BigUint,
+        result: BatchItemResult,
+    );
+
+    #[event("swap_adapter_set_event")]
+    fn swap_adapter_set_event(&self, #[indexed] swap_adapter: &ManagedAddress);
+
+    #[event("fees_swapped_event")]
+    fn fees_swapped_event(
+        &self,
+        #[indexed] token: &TokenIdentifier,
+        #[indexed] amount: &BigUint,
+        egld_amount: &BigUint,
+    );
+
+    #[event("fee_receivers_set_event")]
+    fn fee_receivers_set_event(&self, fee_receivers: &ManagedVec<FeeReceiver<Self::Api>>);
+
+    #[event("fees_distributed_event")]
+    fn fees_distributed_event(
+        &self,
+        #[indexed] receiver: &ManagedAddress,
+        #[indexed] token: &EgldOrEsdtTokenIdentifier,
+        amount: &BigUint,
+    );
+
     #[event("refunded_event")]
     fn refunded_event(
         &self,
@@ -0,0 +1,134 @@
+use crate::accounting;
+use crate::collectorship;
+use crate::constants::ExpressCall;
+use crate::events;
+use crate::payments;
+use multiversx_sc::api::KECCAK256_RESULT_LEN;
+
+multiversx_sc::imports!();
+
+// Express relayers front the execution of a command on the destination chain before it is approved by the gateway,
+// and are reimbursed with the express gas escrowed for it once the approval arrives
+#[multiversx_sc::module]
+pub trait ExpressModule:
+    events::Events
+    + accounting::AccountingModule
+    + payments::PaymentsModule
+    + collectorship::Collectorship
+    + operatable::roles::AccountRoles
+{
+    // Called by the express relayer which fronted the execution, each express payment backing a single command.
+    // Restricted to trusted express relayers, since the command is only checked by a refunder on reimbursement
+    // and anyone could otherwise lock express payments with made up commands
+    #[endpoint(registerExpressCall)]
+    fn register_express_call(
+        &self,
+        command_id: ManagedByteArray<KECCAK256_RESULT_LEN>,
+        payment_id: u64,
+    ) {
+        self.only_express_relayer();
+
+        let express_call_mapper = self.express_calls(&command_id);
+
+        require!(
+            express_call_mapper.is_empty(),
+            "Express call already registered"
+        );
+
+        let payment_mapper = self.payments(payment_id);
+
+        require!(!payment_mapper.is_empty(), "Invalid payment");
+
+        let payment = payment_mapper.get();
+
+        require!(payment.express, "Not express payment");
+        require!(!payment.settled, "Payment already settled");
+
+        let payment_express_call_mapper = self.payment_express_call(payment_id);
+
+        require!(
+            payment_express_call_mapper.is_empty(),
+            "Express payment already used"
+        );
+
+        let relayer = self.blockchain().get_caller();
+
+        self.express_call_registered_event(&command_id, payment_id, &relayer);
+
+        payment_express_call_mapper.set(&command_id);
+        express_call_mapper.set(ExpressCall {
+            payment_id,
+            relayer,
+            reimbursed: false,
+        });
+    }
+
+    // Called once the gateway approval of the command arrived, paying the escrowed express gas to the relayer
+    #[endpoint(reimburseExpressCall)]
+    fn reimburse_express_call(&self, command_id: ManagedByteArray<KECCAK256_RESULT_LEN>) {
+        self.only_refunder();
+
+        let express_call_mapper = self.express_calls(&command_id);
+
+        require!(!express_call_mapper.is_empty(), "Invalid express call");
+
+        let mut express_call = express_call_mapper.get();
+
+        require!(!express_call.reimbursed, "Express call already reimbursed");
+
+        self.payment_express_call(express_call.payment_id).clear();
+
+        let payment = self.payments(express_call.payment_id).get();
+
+        let mut refund_amounts = ManagedVec::new();
+        for _ in 0..payment.tokens.len() {
+            refund_amounts.push(BigUint::zero());
+        }
+
+        let payment = self.settle_payment(express_call.payment_id, refund_amounts);
+
+        for token in payment.tokens.iter() {
+            self.send()
+                .direct(&express_call.relayer, &token.token, 0, &token.amount);
+        }
+
+        self.add_express_reimbursed(&payment.tokens);
+
+        self.express_call_reimbursed_event(
+            &command_id,
+            express_call.payment_id,
+            &express_call.relayer,
+            &payment.tokens,
+        );
+
+        express_call.reimbursed = true;
+        express_call_mapper.set(&express_call);
+    }
+
+    // Rejects a fronting which was never approved by the gateway, so the express payment can be refunded
+    // or used by another relayer
+    #[endpoint(cancelExpressCall)]
+    fn cancel_express_call(&self, command_id: ManagedByteArray<KECCAK256_RESULT_LEN>) {
+        self.only_refunder();
+
+        let express_call_mapper = self.express_calls(&command_id);
+
+        require!(!express_call_mapper.is_empty(), "Invalid express call");
+
+        let express_call = express_call_mapper.get();
+
+        require!(!express_call.reimbursed, "Express call already reimbursed");
+
+        self.express_call_cancelled_event(&command_id, express_call.payment_id);
+
+        self.payment_express_call(express_call.payment_id).clear();
+        express_call_mapper.clear();
+    }
+
+    #[view(expressCalls)]
+    #[storage_mapper("express_calls")]
+    fn express_calls(
+        &self,
+        command_id: &ManagedByteArray<KECCAK256_RESULT_LEN>,
+    ) -> SingleValueMapper<ExpressCall<Self::Api>>;
+}
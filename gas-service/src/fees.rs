@@ -0,0 +1,109 @@
+use crate::constants::{GasFee, GasToken};
+
+multiversx_sc::imports!();
+
+// Fee table managed by the gas collector, used to quote the minimum gas for a cross chain call
+#[multiversx_sc::module]
+pub trait FeesModule {
+    fn require_min_gas_fee(
+        &self,
+        destination_chain: &ManagedBuffer,
+        payload: &ManagedBuffer,
+        token: &EgldOrEsdtTokenIdentifier,
+        amount: &BigUint,
+    ) {
+        if !self.min_gas_fee_enforced().get() {
+            return;
+        }
+
+        let min_gas_fee = self.min_gas_fee(destination_chain, payload.len() as u64, token);
+
+        require!(amount >= &min_gas_fee, "Insufficient gas fee");
+    }
+
+    // Tokens can't be compared to each other, so at least one of them needs to cover its quote on its own.
+    // Every token needs a fee set, otherwise a token without a known minimum would always cover it
+    fn require_min_multi_token_gas_fee(
+        &self,
+        destination_chain: &ManagedBuffer,
+        payload: &ManagedBuffer,
+        payments: &ManagedVec<GasToken<Self::Api>>,
+    ) {
+        if !self.min_gas_fee_enforced().get() {
+            return;
+        }
+
+        let mut covered = false;
+
+        for payment in payments.iter() {
+            let min_gas_fee =
+                self.min_gas_fee(destination_chain, payload.len() as u64, &payment.token);
+
+            if payment.amount >= min_gas_fee {
+                covered = true;
+            }
+        }
+
+        require!(covered, "Insufficient gas fee");
+    }
+
+    // When enforced, tokens without a fee set for the destination chain are not accepted
+    fn min_gas_fee(
+        &self,
+        destination_chain: &ManagedBuffer,
+        payload_len: u64,
+        token: &EgldOrEsdtTokenIdentifier,
+    ) -> BigUint {
+        require!(
+            !self.gas_fees(destination_chain, token).is_empty(),
+            "Gas fee not set"
+        );
+
+        self.quote_gas_fee(destination_chain, payload_len, token)
+    }
+
+    fn quote_gas_fee(
+        &self,
+        destination_chain: &ManagedBuffer,
+        payload_len: u64,
+        token: &EgldOrEsdtTokenIdentifier,
+    ) -> BigUint {
+        let gas_fee_mapper = self.gas_fees(destination_chain, token);
+
+        // No fee configured means no minimum is known for this chain and token
+        if gas_fee_mapper.is_empty() {
+            return BigUint::zero();
+        }
+
+        let gas_fee = gas_fee_mapper.get();
+
+        gas_fee.base_fee + gas_fee.fee_per_byte * payload_len
+    }
+
+    // Fails for tokens the pay gas endpoints would reject, instead of quoting them as free
+    #[view(estimateGasFee)]
+    fn estimate_gas_fee(
+        &self,
+        destination_chain: ManagedBuffer,
+        payload_len: u64,
+        token: EgldOrEsdtTokenIdentifier,
+    ) -> BigUint {
+        if self.min_gas_fee_enforced().get() {
+            return self.min_gas_fee(&destination_chain, payload_len, &token);
+        }
+
+        self.quote_gas_fee(&destination_chain, payload_len, &token)
+    }
+
+    #[view(gasFee)]
+    #[storage_mapper("gas_fees")]
+    fn gas_fees(
+        &self,
+        destination_chain: &ManagedBuffer,
+        token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<GasFee<Self::Api>>;
+
+    #[view(minGasFeeEnforced)]
+    #[storage_mapper("min_gas_fee_enforced")]
+    fn min_gas_fee_enforced(&self) -> SingleValueMapper<bool>;
+}
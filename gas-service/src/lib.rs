@@ -1,23 +1,53 @@
 #![no_std]
 
+use crate::constants::{BatchItemResult, GasFee, GasToken, ESDT_EGLD_IDENTIFIER};
 use crate::events::{
-    AddGasData, AddNativeGasData, GasPaidForContractCallData, NativeGasPaidForContractCallData,
-    RefundedData,
+    AddGasData, AddMultiTokenGasData, AddNativeGasData, GasPaidForContractCallData,
+    MultiTokenGasPaidForContractCallData, NativeGasPaidForContractCallData, RefundedData,
 };
 
+use operatable::roles::Roles;
+
 multiversx_sc::imports!();
 
+mod accounting;
+mod collectorship;
+mod constants;
+mod distribution;
 mod events;
+mod express;
+mod fees;
+mod payments;
+mod swap;
 
 #[multiversx_sc::contract]
-pub trait GasService: events::Events {
+pub trait GasService:
+    events::Events
+    + fees::FeesModule
+    + accounting::AccountingModule
+    + payments::PaymentsModule
+    + express::ExpressModule
+    + distribution::DistributionModule
+    + swap::SwapModule
+    + collectorship::Collectorship
+    + operatable::roles::AccountRoles
+{
     #[init]
-    fn init(&self, gas_collector: &ManagedAddress) {
-        self.gas_collector().set_if_empty(gas_collector);
+    fn init(&self, gas_collector: ManagedAddress) {
+        self.add_role(gas_collector, Roles::COLLECTOR | Roles::REFUNDER);
     }
 
     #[upgrade]
-    fn upgrade(&self) {}
+    fn upgrade(&self) {
+        let gas_collector_mapper = self.gas_collector();
+
+        if !gas_collector_mapper.is_empty() {
+            self.add_role(
+                gas_collector_mapper.take(),
+                Roles::COLLECTOR | Roles::REFUNDER,
+            );
+        }
+    }
 
     #[payable("*")]
     #[endpoint(payGasForContractCall)]
@@ -33,12 +63,25 @@ pub trait GasService: events::Events {
 
         require!(gas_fee_amount > 0, "Nothing received");
 
+        let token = EgldOrEsdtTokenIdentifier::esdt(gas_token.clone());
+
+        self.require_min_gas_fee(&destination_chain, &payload, &token, &gas_fee_amount);
+
+        let payment_id = self.record_payment(
+            token,
+            gas_fee_amount.clone(),
+            refund_address.clone(),
+            Some(&destination_chain),
+            false,
+        );
+
         let hash = self.crypto().keccak256(&payload);
 
         self.gas_paid_for_contract_call_event(
             sender,
             destination_chain,
             destination_address,
+            payment_id,
             GasPaidForContractCallData {
                 hash,
                 gas_token,
@@ -62,12 +105,28 @@ pub trait GasService: events::Events {
 
         require!(value > 0, "Nothing received");
 
+        self.require_min_gas_fee(
+            &destination_chain,
+            &payload,
+            &EgldOrEsdtTokenIdentifier::egld(),
+            &value,
+        );
+
+        let payment_id = self.record_payment(
+            EgldOrEsdtTokenIdentifier::egld(),
+            value.clone(),
+            refund_address.clone(),
+            Some(&destination_chain),
+            false,
+        );
+
         let hash = self.crypto().keccak256(&payload);
 
         self.native_gas_paid_for_contract_call_event(
             sender,
             destination_chain,
             destination_address,
+            payment_id,
             NativeGasPaidForContractCallData {
                 hash,
                 value,
@@ -76,6 +135,43 @@ pub trait GasService: events::Events {
         );
     }
 
+    // Accepts several ESDTs, optionally with EGLD, as gas for a single contract call
+    #[payable("*")]
+    #[endpoint(payMultiTokenGasForContractCall)]
+    fn pay_multi_token_gas_for_contract_call(
+        &self,
+        sender: ManagedAddress,
+        destination_chain: ManagedBuffer,
+        destination_address: ManagedBuffer,
+        payload: ManagedBuffer,
+        refund_address: ManagedAddress,
+    ) {
+        let payments = self.multi_token_gas_payments();
+
+        self.require_min_multi_token_gas_fee(&destination_chain, &payload, &payments);
+
+        let payment_id = self.record_multi_token_payment(
+            payments.clone(),
+            refund_address.clone(),
+            Some(&destination_chain),
+            false,
+        );
+
+        let hash = self.crypto().keccak256(&payload);
+
+        self.multi_token_gas_paid_for_contract_call_event(
+            sender,
+            destination_chain,
+            destination_address,
+            payment_id,
+            MultiTokenGasPaidForContractCallData {
+                hash,
+                payments,
+                refund_address,
+            },
+        );
+    }
+
     #[payable("*")]
     #[endpoint(payGasForExpressCall)]
     fn pay_gas_for_express_call(
@@ -90,12 +186,25 @@ pub trait GasService: events::Events {
 
         require!(gas_fee_amount > 0, "Nothing received");
 
+        let token = EgldOrEsdtTokenIdentifier::esdt(gas_token.clone());
+
+        self.require_min_gas_fee(&destination_chain, &payload, &token, &gas_fee_amount);
+
+        let payment_id = self.record_payment(
+            token,
+            gas_fee_amount.clone(),
+            refund_address.clone(),
+            Some(&destination_chain),
+            true,
+        );
+
         let hash = self.crypto().keccak256(&payload);
 
         self.gas_paid_for_express_call(
             sender,
             destination_chain,
             destination_address,
+            payment_id,
             GasPaidForContractCallData {
                 hash,
                 gas_token,
@@ -119,12 +228,28 @@ pub trait GasService: events::Events {
 
         require!(value > 0, "Nothing received");
 
+        self.require_min_gas_fee(
+            &destination_chain,
+            &payload,
+            &EgldOrEsdtTokenIdentifier::egld(),
+            &value,
+        );
+
+        let payment_id = self.record_payment(
+            EgldOrEsdtTokenIdentifier::egld(),
+            value.clone(),
+            refund_address.clone(),
+            Some(&destination_chain),
+            true,
+        );
+
         let hash = self.crypto().keccak256(&payload);
 
         self.native_gas_paid_for_express_call(
             sender,
             destination_chain,
             destination_address,
+            payment_id,
             NativeGasPaidForContractCallData {
                 hash,
                 value,
@@ -140,9 +265,18 @@ pub trait GasService: events::Events {
 
         require!(gas_fee_amount > 0, "Nothing received");
 
+        let payment_id = self.record_payment(
+            EgldOrEsdtTokenIdentifier::esdt(gas_token.clone()),
+            gas_fee_amount.clone(),
+            refund_address.clone(),
+            None,
+            false,
+        );
+
         self.gas_added_event(
             tx_hash,
             log_index,
+            payment_id,
             AddGasData {
                 gas_token,
                 gas_fee_amount,
@@ -163,9 +297,18 @@ pub trait GasService: events::Events {
 
         require!(value > 0, "Nothing received");
 
+        let payment_id = self.record_payment(
+            EgldOrEsdtTokenIdentifier::egld(),
+            value.clone(),
+            refund_address.clone(),
+            None,
+            false,
+        );
+
         self.native_gas_added_event(
             tx_hash,
             log_index,
+            payment_id,
             AddNativeGasData {
                 value,
                 refund_address,
@@ -173,6 +316,30 @@ pub trait GasService: events::Events {
         );
     }
 
+    #[payable("*")]
+    #[endpoint(addMultiTokenGas)]
+    fn add_multi_token_gas(
+        &self,
+        tx_hash: ManagedBuffer,
+        log_index: BigUint,
+        refund_address: ManagedAddress,
+    ) {
+        let payments = self.multi_token_gas_payments();
+
+        let payment_id =
+            self.record_multi_token_payment(payments.clone(), refund_address.clone(), None, false);
+
+        self.multi_token_gas_added_event(
+            tx_hash,
+            log_index,
+            payment_id,
+            AddMultiTokenGasData {
+                payments,
+                refund_address,
+            },
+        );
+    }
+
     #[payable("*")]
     #[endpoint(addExpressGas)]
     fn add_express_gas(
@@ -185,9 +352,18 @@ pub trait GasService: events::Events {
 
         require!(gas_fee_amount > 0, "Nothing received");
 
+        let payment_id = self.record_payment(
+            EgldOrEsdtTokenIdentifier::esdt(gas_token.clone()),
+            gas_fee_amount.clone(),
+            refund_address.clone(),
+            None,
+            true,
+        );
+
         self.express_gas_added_event(
             tx_hash,
             log_index,
+            payment_id,
             AddGasData {
                 gas_token,
                 gas_fee_amount,
@@ -208,9 +384,18 @@ pub trait GasService: events::Events {
 
         require!(value > 0, "Nothing received");
 
+        let payment_id = self.record_payment(
+            EgldOrEsdtTokenIdentifier::egld(),
+            value.clone(),
+            refund_address.clone(),
+            None,
+            true,
+        );
+
         self.native_express_gas_added_event(
             tx_hash,
             log_index,
+            payment_id,
             AddNativeGasData {
                 value,
                 refund_address,
@@ -218,6 +403,11 @@ pub trait GasService: events::Events {
         );
     }
 
+    // Returns the result of each entry, entries exceeding the balance of the contract (without the escrowed
+    // express gas) being skipped. If minimum EGLD amounts are given, ESDTs are swapped to EGLD through the
+    // swap adapter before being sent.
+    // Unsettled payments don't reserve any balance, since most of them are never refunded, so refunds
+    // need to be processed before collecting the balance backing them
     #[allow_multiple_var_args]
     #[endpoint(collectFees)]
     fn collect_fees(
@@ -225,80 +415,216 @@ pub trait GasService: events::Events {
         receiver: &ManagedAddress,
         tokens: MultiValueManagedVecCounted<EgldOrEsdtTokenIdentifier>,
         amounts: MultiValueManagedVecCounted<BigUint>,
-    ) {
-        self.require_only_collector();
+        min_egld_amounts: OptionalValue<MultiValueManagedVecCounted<BigUint>>,
+    ) -> MultiValueEncoded<BatchItemResult> {
+        self.only_collector();
 
         require!(!receiver.is_zero(), "Invalid address");
 
         let tokens_length = tokens.len();
         require!(tokens_length == amounts.len(), "Invalid amounts");
 
+        let min_egld_amounts = match min_egld_amounts {
+            OptionalValue::Some(min_egld_amounts) => {
+                require!(tokens_length == min_egld_amounts.len(), "Invalid amounts");
+
+                Some(min_egld_amounts.into_vec())
+            }
+            OptionalValue::None => None,
+        };
+
         let tokens_vec = tokens.into_vec();
         let amounts_vec = amounts.into_vec();
 
+        let mut results = MultiValueEncoded::new();
+
         for index in 0..tokens_length {
             let token: EgldOrEsdtTokenIdentifier = tokens_vec.get(index);
             let amount = amounts_vec.get(index).clone_value();
 
             require!(amount > 0, "Invalid amounts");
 
-            let balance = self.blockchain().get_sc_balance(&token, 0);
-
-            if token.is_egld() {
-                if amount <= balance {
-                    self.send().direct_egld(receiver, &amount);
+            let result = if amount <= self.available_balance(&token) {
+                match &min_egld_amounts {
+                    Some(min_egld_amounts) if token.is_esdt() => {
+                        let egld_amount = self.swap_to_egld(
+                            token.clone().unwrap_esdt(),
+                            amount.clone(),
+                            min_egld_amounts.get(index).clone_value(),
+                        );
+
+                        self.send().direct_egld(receiver, &egld_amount);
+                    }
+                    _ => self.send().direct(receiver, &token, 0, &amount),
                 }
-            } else if amount <= balance {
-                self.send().direct_esdt(receiver, &token.unwrap_esdt(), 0, &amount)
-            }
+
+                self.add_fees_collected(&token, &amount);
+
+                BatchItemResult::Success
+            } else {
+                BatchItemResult::InsufficientBalance
+            };
+
+            self.collect_fees_result_event(receiver, &token, &amount, result);
+
+            results.push(result);
         }
+
+        results
     }
 
+    // Refunds the unspent part of a payment to its refund address and settles the payment. One amount
+    // is given for each token of the payment, in the order they were paid
     #[endpoint(refund)]
     fn refund(
         &self,
         tx_hash: ManagedBuffer,
         log_index: BigUint,
-        receiver: ManagedAddress,
+        payment_id: u64,
+        amounts: MultiValueEncoded<BigUint>,
+    ) {
+        self.only_refunder();
+
+        self.refund_payment(&tx_hash, &log_index, payment_id, amounts.to_vec());
+    }
+
+    // Refunds many payments at once, returning the result of each entry. Entries which can't be refunded are skipped
+    #[endpoint(batchRefund)]
+    fn batch_refund(
+        &self,
+        refunds: MultiValueEncoded<MultiValue4<ManagedBuffer, BigUint, u64, ManagedVec<BigUint>>>,
+    ) -> MultiValueEncoded<BatchItemResult> {
+        self.only_refunder();
+
+        let mut results = MultiValueEncoded::new();
+
+        for refund in refunds.into_iter() {
+            let (tx_hash, log_index, payment_id, amounts) = refund.into_tuple();
+
+            let result = self.check_refund(payment_id, &amounts);
+
+            if result == BatchItemResult::Success {
+                self.refund_payment(&tx_hash, &log_index, payment_id, amounts);
+            }
+
+            self.refund_result_event(&tx_hash, &log_index, payment_id, result);
+
+            results.push(result);
+        }
+
+        results
+    }
+
+    #[endpoint(setGasFee)]
+    fn set_gas_fee(
+        &self,
+        destination_chain: ManagedBuffer,
         token: EgldOrEsdtTokenIdentifier,
-        amount: BigUint,
+        base_fee: BigUint,
+        fee_per_byte: BigUint,
     ) {
-        self.require_only_collector();
+        self.only_collector();
 
-        require!(!receiver.is_zero(), "Invalid address");
+        require!(!destination_chain.is_empty(), "Invalid destination chain");
+        require!(token.is_valid(), "Invalid token");
 
-        self.send().direct(&receiver, &token, 0, &amount);
+        self.gas_fee_set_event(&destination_chain, &token, &base_fee, &fee_per_byte);
 
-        self.refunded_event(
-            tx_hash,
-            log_index,
-            RefundedData {
-                receiver,
-                token,
-                amount,
-            },
-        );
+        self.gas_fees(&destination_chain, &token).set(GasFee {
+            base_fee,
+            fee_per_byte,
+        });
+    }
+
+    #[endpoint(removeGasFee)]
+    fn remove_gas_fee(&self, destination_chain: ManagedBuffer, token: EgldOrEsdtTokenIdentifier) {
+        self.only_collector();
+
+        self.gas_fees(&destination_chain, &token).clear();
+
+        self.gas_fee_removed_event(&destination_chain, &token);
+    }
+
+    // When enforced, pay gas endpoints reject payments below the fee quoted by estimateGasFee
+    #[endpoint(setMinGasFeeEnforced)]
+    fn set_min_gas_fee_enforced(&self, enforced: bool) {
+        self.only_collector();
+
+        self.min_gas_fee_enforced_set_event(enforced);
+
+        self.min_gas_fee_enforced().set(enforced);
     }
 
-    #[endpoint(setGasCollector)]
-    fn set_gas_collector(&self, gas_collector: &ManagedAddress) {
-        let caller = self.blockchain().get_caller();
-        let collector = self.gas_collector().get();
-        let owner = self.blockchain().get_owner_address();
+    fn refund_payment(
+        &self,
+        tx_hash: &ManagedBuffer,
+        log_index: &BigUint,
+        payment_id: u64,
+        amounts: ManagedVec<BigUint>,
+    ) {
+        let payment = self.settle_payment(payment_id, amounts);
 
-        require!(caller == collector || caller == owner, "Not collector or owner");
+        for (token, amount) in payment.tokens.iter().zip(payment.refunded.iter()) {
+            if *amount == 0 {
+                continue;
+            }
 
-        self.gas_collector().set(gas_collector);
+            self.send()
+                .direct(&payment.refund_address, &token.token, 0, &amount);
+
+            self.refunded_event(
+                tx_hash.clone(),
+                log_index.clone(),
+                RefundedData {
+                    receiver: payment.refund_address.clone(),
+                    token: token.token,
+                    amount: amount.clone_value(),
+                },
+            );
+        }
     }
 
-    fn require_only_collector(&self) {
-        let caller = self.blockchain().get_caller();
-        let collector = self.gas_collector().get();
+    // EGLD is accepted either as the call value or as EGLD-000000 in a multi ESDT transfer
+    fn multi_token_gas_payments(&self) -> ManagedVec<GasToken<Self::Api>> {
+        let mut payments = ManagedVec::new();
+
+        let value = self.call_value().egld_value().clone_value();
+        if value > 0 {
+            payments.push(GasToken {
+                token: EgldOrEsdtTokenIdentifier::egld(),
+                amount: value,
+            });
+        }
+
+        for esdt in self.call_value().all_esdt_transfers().iter() {
+            require!(esdt.token_nonce == 0, "Only fungible esdts are supported");
+            require!(esdt.amount > 0, "Nothing received");
+
+            let token = if esdt.token_identifier.as_managed_buffer()
+                == &ManagedBuffer::from(ESDT_EGLD_IDENTIFIER)
+            {
+                EgldOrEsdtTokenIdentifier::egld()
+            } else {
+                EgldOrEsdtTokenIdentifier::esdt(esdt.token_identifier)
+            };
+
+            require!(
+                !payments.iter().any(|payment| payment.token == token),
+                "Duplicate token"
+            );
+
+            payments.push(GasToken {
+                token,
+                amount: esdt.amount,
+            });
+        }
+
+        require!(!payments.is_empty(), "Nothing received");
 
-        require!(caller == collector, "Not collector");
+        payments
     }
 
-    #[view]
+    // Gas collector set before the collector and refunder roles were introduced
     #[storage_mapper("gas_collector")]
     fn gas_collector(&self) -> SingleValueMapper<ManagedAddress>;
 }
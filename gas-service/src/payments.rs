@@ -0,0 +1,189 @@
+use crate::accounting;
+use crate::constants::{BatchItemResult, GasPayment, GasToken};
+use crate::events;
+use multiversx_sc::api::KECCAK256_RESULT_LEN;
+
+multiversx_sc::imports!();
+
+// Keeps a ledger of every gas payment, so refunds can never exceed what was actually paid
+#[multiversx_sc::module]
+pub trait PaymentsModule: events::Events + accounting::AccountingModule {
+    fn record_payment(
+        &self,
+        token: EgldOrEsdtTokenIdentifier,
+        amount: BigUint,
+        refund_address: ManagedAddress,
+        destination_chain: Option<&ManagedBuffer>,
+        express: bool,
+    ) -> u64 {
+        self.record_multi_token_payment(
+            ManagedVec::from_single_item(GasToken { token, amount }),
+            refund_address,
+            destination_chain,
+            express,
+        )
+    }
+
+    fn record_multi_token_payment(
+        &self,
+        tokens: ManagedVec<GasToken<Self::Api>>,
+        refund_address: ManagedAddress,
+        destination_chain: Option<&ManagedBuffer>,
+        express: bool,
+    ) -> u64 {
+        let payment_id = self.last_payment_id().update(|id| {
+            *id += 1;
+            *id
+        });
+
+        self.gas_payment_recorded_event(payment_id, &refund_address, &tokens);
+
+        self.add_gas_paid(payment_id, destination_chain, &tokens);
+
+        if express {
+            for token in tokens.iter() {
+                self.express_escrow(&token.token)
+                    .update(|escrow| *escrow += &token.amount);
+            }
+        }
+
+        self.payments(payment_id).set(GasPayment {
+            tokens,
+            refund_address,
+            refunded: ManagedVec::new(),
+            settled: false,
+            express,
+        });
+
+        payment_id
+    }
+
+    // Refund amounts are given for each token of the payment, in order
+    fn check_refund(
+        &self,
+        payment_id: u64,
+        refund_amounts: &ManagedVec<BigUint>,
+    ) -> BatchItemResult {
+        let payment_mapper = self.payments(payment_id);
+
+        if payment_mapper.is_empty() {
+            return BatchItemResult::InvalidPayment;
+        }
+
+        let payment = payment_mapper.get();
+
+        if payment.settled {
+            return BatchItemResult::PaymentAlreadySettled;
+        }
+
+        if !self.payment_express_call(payment_id).is_empty() {
+            return BatchItemResult::ExpressCallPending;
+        }
+
+        if refund_amounts.len() != payment.tokens.len() {
+            return BatchItemResult::InvalidAmounts;
+        }
+
+        for (token, refund_amount) in payment.tokens.iter().zip(refund_amounts.iter()) {
+            if *refund_amount > token.amount {
+                return BatchItemResult::RefundExceedsPayment;
+            }
+
+            // Express gas of other payments is escrowed for their relayers, so it can only back express payments
+            let balance = if payment.express {
+                self.blockchain().get_sc_balance(&token.token, 0)
+            } else {
+                self.available_balance(&token.token)
+            };
+
+            if *refund_amount > balance {
+                return BatchItemResult::InsufficientBalance;
+            }
+        }
+
+        BatchItemResult::Success
+    }
+
+    fn settle_payment(
+        &self,
+        payment_id: u64,
+        refund_amounts: ManagedVec<BigUint>,
+    ) -> GasPayment<Self::Api> {
+        let result = self.check_refund(payment_id, &refund_amounts);
+
+        if result != BatchItemResult::Success {
+            sc_panic!(result.error_message());
+        }
+
+        let payment_mapper = self.payments(payment_id);
+        let mut payment = payment_mapper.get();
+
+        payment.refunded = refund_amounts;
+        payment.settled = true;
+
+        if payment.express {
+            for token in payment.tokens.iter() {
+                self.express_escrow(&token.token)
+                    .update(|escrow| *escrow -= &token.amount);
+            }
+        }
+
+        self.add_gas_refunded(payment_id, &payment.tokens, &payment.refunded);
+
+        payment_mapper.set(&payment);
+
+        self.gas_payment_settled_event(payment_id, &payment.refunded);
+
+        payment
+    }
+
+    // The amounts which can still be refunded for a payment
+    #[view(paymentBalance)]
+    fn payment_balance(&self, payment_id: u64) -> ManagedVec<GasToken<Self::Api>> {
+        let payment_mapper = self.payments(payment_id);
+
+        if payment_mapper.is_empty() {
+            return ManagedVec::new();
+        }
+
+        let payment = payment_mapper.get();
+
+        if payment.settled {
+            return ManagedVec::new();
+        }
+
+        payment.tokens
+    }
+
+    // Balance which can be collected as fees, express gas being escrowed
+    fn available_balance(&self, token: &EgldOrEsdtTokenIdentifier) -> BigUint {
+        let balance = self.blockchain().get_sc_balance(token, 0);
+        let escrow = self.express_escrow(token).get();
+
+        if balance <= escrow {
+            return BigUint::zero();
+        }
+
+        balance - escrow
+    }
+
+    #[view(lastPaymentId)]
+    #[storage_mapper("last_payment_id")]
+    fn last_payment_id(&self) -> SingleValueMapper<u64>;
+
+    #[view]
+    #[storage_mapper("payments")]
+    fn payments(&self, payment_id: u64) -> SingleValueMapper<GasPayment<Self::Api>>;
+
+    #[view(expressEscrow)]
+    #[storage_mapper("express_escrow")]
+    fn express_escrow(&self, token: &EgldOrEsdtTokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    // Command of the express call an express payment is used for, until the express relayer is reimbursed
+    #[view(paymentExpressCall)]
+    #[storage_mapper("payment_express_call")]
+    fn payment_express_call(
+        &self,
+        payment_id: u64,
+    ) -> SingleValueMapper<ManagedByteArray<KECCAK256_RESULT_LEN>>;
+}
@@ -0,0 +1,87 @@
+use crate::collectorship;
+use crate::events;
+
+multiversx_sc::imports!();
+
+pub mod swap_adapter_proxy {
+    multiversx_sc::imports!();
+
+    // Interface of the swap adapters used to convert collected ESDT fees into EGLD. Adapters swap the received ESDT
+    // and send at least `min_amount_out` EGLD back to the caller
+    #[multiversx_sc::proxy]
+    pub trait SwapAdapterProxy {
+        #[payable("*")]
+        #[endpoint(swapTokensToEgld)]
+        fn swap_tokens_to_egld(&self, min_amount_out: BigUint) -> BigUint;
+    }
+}
+
+#[multiversx_sc::module]
+pub trait SwapModule:
+    events::Events + collectorship::Collectorship + operatable::roles::AccountRoles
+{
+    #[endpoint(setSwapAdapter)]
+    fn set_swap_adapter(&self, swap_adapter: ManagedAddress) {
+        self.only_collector();
+
+        require!(
+            self.blockchain().is_smart_contract(&swap_adapter),
+            "Invalid swap adapter"
+        );
+
+        self.swap_adapter_set_event(&swap_adapter);
+
+        self.swap_adapter().set(swap_adapter);
+    }
+
+    #[endpoint(removeSwapAdapter)]
+    fn remove_swap_adapter(&self) {
+        self.only_collector();
+
+        self.swap_adapter_set_event(&ManagedAddress::zero());
+
+        self.swap_adapter().clear();
+    }
+
+    // The received amount is checked against the minimum, regardless of what the adapter reports
+    fn swap_to_egld(
+        &self,
+        token: TokenIdentifier,
+        amount: BigUint,
+        min_amount_out: BigUint,
+    ) -> BigUint {
+        let swap_adapter_mapper = self.swap_adapter();
+
+        require!(!swap_adapter_mapper.is_empty(), "No swap adapter");
+
+        let egld_balance_before = self
+            .blockchain()
+            .get_sc_balance(&EgldOrEsdtTokenIdentifier::egld(), 0);
+
+        self.swap_adapter_proxy(swap_adapter_mapper.get())
+            .swap_tokens_to_egld(&min_amount_out)
+            .with_esdt_transfer((token.clone(), 0, amount.clone()))
+            .execute_on_dest_context::<IgnoreValue>();
+
+        let amount_out = self
+            .blockchain()
+            .get_sc_balance(&EgldOrEsdtTokenIdentifier::egld(), 0)
+            - egld_balance_before;
+
+        require!(amount_out >= min_amount_out, "Slippage exceeded");
+
+        self.fees_swapped_event(&token, &amount, &amount_out);
+
+        amount_out
+    }
+
+    #[proxy]
+    fn swap_adapter_proxy(
+        &self,
+        sc_address: ManagedAddress,
+    ) -> swap_adapter_proxy::Proxy<Self::Api>;
+
+    #[view(getSwapAdapter)]
+    #[storage_mapper("swap_adapter")]
+    fn swap_adapter(&self) -> SingleValueMapper<ManagedAddress>;
+}
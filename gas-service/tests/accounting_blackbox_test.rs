@@ -0,0 +1,353 @@
+mod common;
+
+use common::*;
+use multiversx_sc_scenario::imports::*;
+
+const RELAYER: TestAddress = TestAddress::new("relayer");
+
+const COMMAND_ID: [u8; 32] = [0x11; 32];
+
+fn setup() -> ScenarioWorld {
+    let mut world = common::setup();
+
+    world.account(RELAYER).nonce(1);
+
+    world
+        .tx()
+        .from(OWNER)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("addExpressRelayer")
+        .argument(&RELAYER)
+        .run();
+
+    world
+}
+
+fn register_express_call(world: &mut ScenarioWorld, from: TestAddress, payment_id: u64) {
+    world
+        .tx()
+        .from(from)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("registerExpressCall")
+        .argument(&ManagedByteArray::<StaticApi, 32>::from(&COMMAND_ID))
+        .argument(&payment_id)
+        .run();
+}
+
+fn query_total(world: &mut ScenarioWorld, view: &str, token: &str) -> RustBigUint {
+    world
+        .query()
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call(view)
+        .argument(&token)
+        .original_result::<BigUint<StaticApi>>()
+        .returns(ReturnsResultUnmanaged)
+        .run()
+}
+
+fn query_chain_total(world: &mut ScenarioWorld, view: &str, token: &str) -> RustBigUint {
+    world
+        .query()
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call(view)
+        .argument(&DESTINATION_CHAIN)
+        .argument(&token)
+        .original_result::<BigUint<StaticApi>>()
+        .returns(ReturnsResultUnmanaged)
+        .run()
+}
+
+fn query_payment_destination_chain(world: &mut ScenarioWorld, payment_id: u64) -> String {
+    let destination_chain = world
+        .query()
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("paymentDestinationChain")
+        .argument(&payment_id)
+        .original_result::<ManagedBuffer<StaticApi>>()
+        .returns(ReturnsResult)
+        .run();
+
+    String::from_utf8(destination_chain.to_vec()).unwrap()
+}
+
+#[test]
+fn accounting_totals() {
+    let mut world = setup();
+
+    pay_native_gas(&mut world, "payNativeGasForContractCall", 1_000);
+
+    world
+        .tx()
+        .from(USER)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("addGas")
+        .argument(&"txHash")
+        .argument(&1u64)
+        .argument(&USER)
+        .single_esdt(&TOKEN.into(), 0, &BigUint::from(500u64))
+        .run();
+
+    pay_native_gas(&mut world, "payNativeGasForExpressCall", 400);
+
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("refund")
+        .argument(&"txHash")
+        .argument(&1u64)
+        .argument(&1u64)
+        .argument(&300u64)
+        .run();
+
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("collectFees")
+        .argument(&COLLECTOR)
+        .argument(&1u32)
+        .argument(&"EGLD")
+        .argument(&1u32)
+        .argument(&200u64)
+        .run();
+
+    register_express_call(&mut world, RELAYER, 3);
+
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("reimburseExpressCall")
+        .argument(&ManagedByteArray::<StaticApi, 32>::from(&COMMAND_ID))
+        .run();
+
+    world
+        .check_account(GAS_SERVICE_ADDRESS)
+        .balance(500)
+        .esdt_balance(TOKEN, 500);
+
+    assert_eq!(
+        query_total(&mut world, "totalGasPaid", "EGLD"),
+        1_400u64.into()
+    );
+    assert_eq!(
+        query_total(&mut world, "totalGasPaid", "TOKEN-123456"),
+        500u64.into()
+    );
+    assert_eq!(
+        query_total(&mut world, "totalGasRefunded", "EGLD"),
+        300u64.into()
+    );
+    assert_eq!(
+        query_total(&mut world, "totalFeesCollected", "EGLD"),
+        200u64.into()
+    );
+    assert_eq!(
+        query_total(&mut world, "totalExpressReimbursed", "EGLD"),
+        400u64.into()
+    );
+
+    // Gas added to an existing payment has no destination chain
+    assert_eq!(
+        query_chain_total(&mut world, "totalGasPaidForChain", "EGLD"),
+        1_400u64.into()
+    );
+    assert_eq!(
+        query_chain_total(&mut world, "totalGasPaidForChain", "TOKEN-123456"),
+        0u64.into()
+    );
+    assert_eq!(
+        query_chain_total(&mut world, "totalGasRefundedForChain", "EGLD"),
+        300u64.into()
+    );
+
+    assert_eq!(
+        query_payment_destination_chain(&mut world, 1),
+        DESTINATION_CHAIN
+    );
+    assert_eq!(query_payment_destination_chain(&mut world, 2), "");
+
+    // Outstanding balances match the balances of the contract
+    assert_eq!(
+        query_total(&mut world, "outstandingBalance", "EGLD"),
+        500u64.into()
+    );
+    assert_eq!(
+        query_total(&mut world, "outstandingBalance", "TOKEN-123456"),
+        500u64.into()
+    );
+}
+
+#[test]
+fn outstanding_balance_untracked_payments() {
+    let mut world = setup();
+
+    // Balance held before totals were tracked, eg. by a previous version of the contract
+    world.set_egld_balance(GAS_SERVICE_ADDRESS, 1_000u64);
+
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("collectFees")
+        .argument(&COLLECTOR)
+        .argument(&1u32)
+        .argument(&"EGLD")
+        .argument(&1u32)
+        .argument(&1_000u64)
+        .run();
+
+    assert_eq!(
+        query_total(&mut world, "totalFeesCollected", "EGLD"),
+        1_000u64.into()
+    );
+    assert_eq!(
+        query_total(&mut world, "outstandingBalance", "EGLD"),
+        0u64.into()
+    );
+}
+
+#[test]
+fn register_express_call_not_express_relayer() {
+    let mut world = setup();
+
+    pay_native_gas(&mut world, "payNativeGasForExpressCall", 400);
+
+    world
+        .tx()
+        .from(USER)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("registerExpressCall")
+        .argument(&ManagedByteArray::<StaticApi, 32>::from(&COMMAND_ID))
+        .argument(&1u64)
+        .returns(ExpectError(4, "Not express relayer"))
+        .run();
+
+    world
+        .tx()
+        .from(OWNER)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("removeExpressRelayer")
+        .argument(&RELAYER)
+        .run();
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("registerExpressCall")
+        .argument(&ManagedByteArray::<StaticApi, 32>::from(&COMMAND_ID))
+        .argument(&1u64)
+        .returns(ExpectError(4, "Not express relayer"))
+        .run();
+}
+
+#[test]
+fn refund_does_not_use_express_escrow() {
+    let mut world = setup();
+
+    pay_native_gas(&mut world, "payNativeGasForExpressCall", 400);
+    pay_native_gas(&mut world, "payNativeGasForContractCall", 1_000);
+
+    // Everything but the express escrow is collected
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("collectFees")
+        .argument(&COLLECTOR)
+        .argument(&1u32)
+        .argument(&"EGLD")
+        .argument(&1u32)
+        .argument(&1_000u64)
+        .run();
+
+    // The escrowed express gas can not be used to refund other payments
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("refund")
+        .argument(&"txHash")
+        .argument(&1u64)
+        .argument(&2u64)
+        .argument(&300u64)
+        .returns(ExpectError(4, "Insufficient balance"))
+        .run();
+
+    register_express_call(&mut world, RELAYER, 1);
+
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("reimburseExpressCall")
+        .argument(&ManagedByteArray::<StaticApi, 32>::from(&COMMAND_ID))
+        .run();
+
+    world.check_account(RELAYER).balance(400);
+    world.check_account(GAS_SERVICE_ADDRESS).balance(0);
+
+    assert_eq!(
+        query_total(&mut world, "totalExpressReimbursed", "EGLD"),
+        400u64.into()
+    );
+    assert_eq!(
+        query_total(&mut world, "outstandingBalance", "EGLD"),
+        0u64.into()
+    );
+}
+
+#[test]
+fn refund_after_collecting_fees() {
+    let mut world = setup();
+
+    pay_native_gas(&mut world, "payNativeGasForContractCall", 1_000);
+
+    // Unsettled payments don't reserve any balance, so it can all be collected
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("collectFees")
+        .argument(&COLLECTOR)
+        .argument(&1u32)
+        .argument(&"EGLD")
+        .argument(&1u32)
+        .argument(&1_000u64)
+        .run();
+
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("refund")
+        .argument(&"txHash")
+        .argument(&1u64)
+        .argument(&1u64)
+        .argument(&300u64)
+        .returns(ExpectError(4, "Insufficient balance"))
+        .run();
+
+    // The payment is left unsettled, so it can be refunded once the contract holds enough balance again
+    pay_native_gas(&mut world, "payNativeGasForContractCall", 1_000);
+
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("refund")
+        .argument(&"txHash")
+        .argument(&1u64)
+        .argument(&1u64)
+        .argument(&300u64)
+        .run();
+
+    world.check_account(GAS_SERVICE_ADDRESS).balance(700);
+
+    assert_eq!(
+        query_total(&mut world, "totalGasRefunded", "EGLD"),
+        300u64.into()
+    );
+}
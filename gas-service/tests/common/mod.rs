@@ -0,0 +1,81 @@
+#![allow(dead_code)]
+
+use multiversx_sc_scenario::imports::*;
+
+pub const OWNER: TestAddress = TestAddress::new("owner");
+pub const COLLECTOR: TestAddress = TestAddress::new("collector");
+pub const USER: TestAddress = TestAddress::new("user");
+pub const GAS_SERVICE_ADDRESS: TestSCAddress = TestSCAddress::new("gas-service");
+pub const DEX_ADDRESS: TestSCAddress = TestSCAddress::new("mock-dex");
+pub const CODE_PATH: MxscPath = MxscPath::new("output/gas-service.mxsc.json");
+pub const DEX_CODE_PATH: MxscPath = MxscPath::new("../mock-dex/output/mock-dex.mxsc.json");
+
+pub const TOKEN: TestTokenIdentifier = TestTokenIdentifier::new("TOKEN-123456");
+pub const OTHER_TOKEN: TestTokenIdentifier = TestTokenIdentifier::new("OTHER-123456");
+
+pub const DESTINATION_CHAIN: &str = "ethereum";
+pub const DESTINATION_ADDRESS: &str = "0x4976da71bF84D750b5451B053051158EC0A4E876";
+
+pub fn world() -> ScenarioWorld {
+    let mut blockchain = ScenarioWorld::new();
+
+    blockchain.register_contract(CODE_PATH, gas_service::ContractBuilder);
+    blockchain.register_contract(DEX_CODE_PATH, mock_dex::ContractBuilder);
+
+    blockchain
+}
+
+// Deploys the Gas Service with COLLECTOR as gas collector, USER holding EGLD and tokens to pay gas with
+pub fn setup() -> ScenarioWorld {
+    let mut world = world();
+
+    world.account(OWNER).nonce(1).balance(10_000);
+    world.account(COLLECTOR).nonce(1);
+    world
+        .account(USER)
+        .nonce(1)
+        .balance(10_000)
+        .esdt_balance(TOKEN, 1_000)
+        .esdt_balance(OTHER_TOKEN, 500);
+
+    world
+        .tx()
+        .from(OWNER)
+        .raw_deploy()
+        .code(CODE_PATH)
+        .new_address(GAS_SERVICE_ADDRESS)
+        .argument(&COLLECTOR)
+        .run();
+
+    world
+}
+
+pub fn pay_gas(world: &mut ScenarioWorld, token: TestTokenIdentifier, amount: u64) {
+    world
+        .tx()
+        .from(USER)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("payGasForContractCall")
+        .argument(&USER)
+        .argument(&DESTINATION_CHAIN)
+        .argument(&DESTINATION_ADDRESS)
+        .argument(&"payload")
+        .argument(&USER)
+        .single_esdt(&token.into(), 0, &BigUint::from(amount))
+        .run();
+}
+
+pub fn pay_native_gas(world: &mut ScenarioWorld, endpoint: &str, value: u64) {
+    world
+        .tx()
+        .from(USER)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call(endpoint)
+        .argument(&USER)
+        .argument(&DESTINATION_CHAIN)
+        .argument(&DESTINATION_ADDRESS)
+        .argument(&"payload")
+        .argument(&USER)
+        .egld(value)
+        .run();
+}
@@ -0,0 +1,226 @@
+mod common;
+
+use common::*;
+use multiversx_sc_scenario::imports::*;
+
+const RECEIVER_1: TestAddress = TestAddress::new("receiver-1");
+const RECEIVER_2: TestAddress = TestAddress::new("receiver-2");
+const RECEIVER_3: TestAddress = TestAddress::new("receiver-3");
+
+fn setup() -> ScenarioWorld {
+    let mut world = common::setup();
+
+    world.account(RECEIVER_1).nonce(1);
+    world.account(RECEIVER_2).nonce(1);
+    world.account(RECEIVER_3).nonce(1);
+
+    world
+}
+
+fn set_fee_receivers_raw(
+    world: &mut ScenarioWorld,
+    from: TestAddress,
+    fee_receivers: &[(TestAddress, u32)],
+    expected_error: Option<&str>,
+) {
+    let mut args = ManagedArgBuffer::<StaticApi>::new();
+    for (receiver, share_bps) in fee_receivers {
+        args.push_arg(receiver.eval_to_array());
+        args.push_arg(share_bps);
+    }
+
+    let tx = world
+        .tx()
+        .from(from)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("setFeeReceivers")
+        .arguments_raw(args);
+
+    match expected_error {
+        Some(message) => tx.returns(ExpectError(4, message)).run(),
+        None => tx.run(),
+    }
+}
+
+fn set_fee_receivers(world: &mut ScenarioWorld, fee_receivers: &[(TestAddress, u32)]) {
+    set_fee_receivers_raw(world, OWNER, fee_receivers, None);
+}
+
+fn set_fee_receivers_error(
+    world: &mut ScenarioWorld,
+    from: TestAddress,
+    fee_receivers: &[(TestAddress, u32)],
+    message: &str,
+) {
+    set_fee_receivers_raw(world, from, fee_receivers, Some(message));
+}
+
+fn add_gas(world: &mut ScenarioWorld, amount: u64) {
+    world
+        .tx()
+        .from(USER)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("addGas")
+        .argument(&"txHash")
+        .argument(&1u64)
+        .argument(&USER)
+        .single_esdt(&TOKEN.into(), 0, &BigUint::from(amount))
+        .run();
+}
+
+#[test]
+fn set_fee_receivers_errors() {
+    let mut world = setup();
+
+    set_fee_receivers_error(
+        &mut world,
+        COLLECTOR,
+        &[(RECEIVER_1, 10_000)],
+        "Endpoint can only be called by owner",
+    );
+
+    set_fee_receivers_error(
+        &mut world,
+        OWNER,
+        &[(RECEIVER_1, 5_000), (RECEIVER_2, 4_999)],
+        "Invalid shares",
+    );
+    set_fee_receivers_error(
+        &mut world,
+        OWNER,
+        &[(RECEIVER_1, 5_000), (RECEIVER_2, 5_001)],
+        "Invalid shares",
+    );
+    set_fee_receivers_error(
+        &mut world,
+        OWNER,
+        &[(RECEIVER_1, 10_000), (RECEIVER_2, u32::MAX)],
+        "Invalid shares",
+    );
+    set_fee_receivers_error(
+        &mut world,
+        OWNER,
+        &[(RECEIVER_1, 10_000), (RECEIVER_2, 0)],
+        "Invalid shares",
+    );
+    set_fee_receivers_error(
+        &mut world,
+        OWNER,
+        &[(RECEIVER_1, 5_000), (RECEIVER_1, 5_000)],
+        "Duplicate receiver",
+    );
+}
+
+#[test]
+fn distribute_fees_no_fee_receivers() {
+    let mut world = setup();
+
+    pay_native_gas(&mut world, "payNativeGasForContractCall", 1_000);
+
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("distributeFees")
+        .argument(&"EGLD")
+        .returns(ExpectError(4, "No fee receivers"))
+        .run();
+
+    // Clearing the table disables distribution again
+    set_fee_receivers(&mut world, &[(RECEIVER_1, 10_000)]);
+    set_fee_receivers(&mut world, &[]);
+
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("distributeFees")
+        .argument(&"EGLD")
+        .returns(ExpectError(4, "No fee receivers"))
+        .run();
+}
+
+#[test]
+fn distribute_fees_not_collector() {
+    let mut world = setup();
+
+    set_fee_receivers(&mut world, &[(RECEIVER_1, 10_000)]);
+
+    world
+        .tx()
+        .from(USER)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("distributeFees")
+        .argument(&"EGLD")
+        .returns(ExpectError(4, "Not collector"))
+        .run();
+}
+
+#[test]
+fn distribute_fees() {
+    let mut world = setup();
+
+    set_fee_receivers(
+        &mut world,
+        &[
+            (RECEIVER_1, 3_333),
+            (RECEIVER_2, 3_333),
+            (RECEIVER_3, 3_334),
+        ],
+    );
+
+    pay_native_gas(&mut world, "payNativeGasForContractCall", 1_000);
+    add_gas(&mut world, 7);
+
+    // Escrowed express gas is not distributed
+    pay_native_gas(&mut world, "payNativeGasForExpressCall", 500);
+
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("distributeFees")
+        .argument(&"EGLD")
+        .argument(&"TOKEN-123456")
+        .run();
+
+    // Shares are rounded down, with the remainder going to the first receiver
+    world
+        .check_account(RECEIVER_1)
+        .balance(334)
+        .esdt_balance(TOKEN, 3);
+    world
+        .check_account(RECEIVER_2)
+        .balance(333)
+        .esdt_balance(TOKEN, 2);
+    world
+        .check_account(RECEIVER_3)
+        .balance(333)
+        .esdt_balance(TOKEN, 2);
+    world
+        .check_account(GAS_SERVICE_ADDRESS)
+        .balance(500)
+        .esdt_balance(TOKEN, 0);
+
+    let collected = world
+        .query()
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("totalFeesCollected")
+        .argument(&"EGLD")
+        .original_result::<BigUint<StaticApi>>()
+        .returns(ReturnsResultUnmanaged)
+        .run();
+    assert_eq!(collected, 1_000u64.into());
+
+    // Nothing left to distribute
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("distributeFees")
+        .argument(&"EGLD")
+        .argument(&"TOKEN-123456")
+        .run();
+
+    world.check_account(RECEIVER_1).balance(334);
+}
@@ -0,0 +1,189 @@
+mod common;
+
+use common::*;
+use multiversx_sc_scenario::imports::*;
+
+// Deploys the Gas Service and a mock DEX swapping TOKEN for half its amount in EGLD,
+// with gas already paid in 1_000 TOKEN and 500 OTHER_TOKEN
+fn setup() -> ScenarioWorld {
+    let mut world = common::setup();
+
+    world
+        .tx()
+        .from(OWNER)
+        .raw_deploy()
+        .code(DEX_CODE_PATH)
+        .new_address(DEX_ADDRESS)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER)
+        .to(DEX_ADDRESS)
+        .raw_call("setRate")
+        .argument(&TOKEN)
+        .argument(&5_000u64)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER)
+        .to(DEX_ADDRESS)
+        .raw_call("deposit")
+        .egld(10_000)
+        .run();
+
+    pay_gas(&mut world, TOKEN, 1_000);
+    pay_gas(&mut world, OTHER_TOKEN, 500);
+
+    world
+}
+
+fn set_swap_adapter(world: &mut ScenarioWorld) {
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("setSwapAdapter")
+        .argument(&DEX_ADDRESS)
+        .run();
+}
+
+fn collect_fees_swapped_raw(
+    world: &mut ScenarioWorld,
+    token: TestTokenIdentifier,
+    amount: u64,
+    min_egld_amount: u64,
+    expected_error: Option<&str>,
+) {
+    let tx = world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("collectFees")
+        .argument(&COLLECTOR)
+        .argument(&1u32)
+        .argument(&token)
+        .argument(&1u32)
+        .argument(&amount)
+        .argument(&1u32)
+        .argument(&min_egld_amount);
+
+    match expected_error {
+        Some(message) => tx.returns(ExpectError(4, message)).run(),
+        None => tx.run(),
+    }
+}
+
+// Collects a single token, swapping it to EGLD with the given minimum amount out
+fn collect_fees_swapped(
+    world: &mut ScenarioWorld,
+    token: TestTokenIdentifier,
+    amount: u64,
+    min_egld_amount: u64,
+) {
+    collect_fees_swapped_raw(world, token, amount, min_egld_amount, None);
+}
+
+fn collect_fees_swapped_error(
+    world: &mut ScenarioWorld,
+    token: TestTokenIdentifier,
+    amount: u64,
+    min_egld_amount: u64,
+    message: &str,
+) {
+    collect_fees_swapped_raw(world, token, amount, min_egld_amount, Some(message));
+}
+
+#[test]
+fn set_swap_adapter_not_smart_contract() {
+    let mut world = setup();
+
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("setSwapAdapter")
+        .argument(&USER)
+        .returns(ExpectError(4, "Invalid swap adapter"))
+        .run();
+}
+
+#[test]
+fn set_swap_adapter_not_collector() {
+    let mut world = setup();
+
+    world
+        .tx()
+        .from(USER)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("setSwapAdapter")
+        .argument(&DEX_ADDRESS)
+        .returns(ExpectError(4, "Not collector"))
+        .run();
+}
+
+#[test]
+fn collect_fees_swapped_no_swap_adapter() {
+    let mut world = setup();
+
+    collect_fees_swapped_error(&mut world, TOKEN, 1_000, 500, "No swap adapter");
+}
+
+#[test]
+fn collect_fees_swapped_success() {
+    let mut world = setup();
+
+    set_swap_adapter(&mut world);
+
+    collect_fees_swapped(&mut world, TOKEN, 400, 200);
+
+    world.check_account(COLLECTOR).balance(200);
+    world
+        .check_account(GAS_SERVICE_ADDRESS)
+        .balance(0)
+        .esdt_balance(TOKEN, 600)
+        .esdt_balance(OTHER_TOKEN, 500);
+    world
+        .check_account(DEX_ADDRESS)
+        .balance(9_800)
+        .esdt_balance(TOKEN, 400);
+}
+
+#[test]
+fn collect_fees_swapped_slippage_exceeded() {
+    let mut world = setup();
+
+    set_swap_adapter(&mut world);
+
+    collect_fees_swapped_error(&mut world, TOKEN, 400, 201, "Slippage exceeded");
+
+    world
+        .check_account(GAS_SERVICE_ADDRESS)
+        .esdt_balance(TOKEN, 1_000);
+}
+
+#[test]
+fn collect_fees_swapped_token_not_supported() {
+    let mut world = setup();
+
+    set_swap_adapter(&mut world);
+
+    collect_fees_swapped_error(&mut world, OTHER_TOKEN, 500, 0, "Token not supported");
+}
+
+#[test]
+fn remove_swap_adapter() {
+    let mut world = setup();
+
+    set_swap_adapter(&mut world);
+
+    world
+        .tx()
+        .from(COLLECTOR)
+        .to(GAS_SERVICE_ADDRESS)
+        .raw_call("removeSwapAdapter")
+        .run();
+
+    collect_fees_swapped_error(&mut world, TOKEN, 1_000, 500, "No swap adapter");
+}
@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           12
+// Endpoints:                           57
 // Async Callback (empty):               1
-// Total number of exported functions:  15
+// Total number of exported functions:  60
 
 #![no_std]
 
@@ -22,16 +22,61 @@ multiversx_sc_wasm_adapter::endpoints! {
         upgrade => upgrade
         payGasForContractCall => pay_gas_for_contract_call
         payNativeGasForContractCall => pay_native_gas_for_contract_call
+        payMultiTokenGasForContractCall => pay_multi_token_gas_for_contract_call
         payGasForExpressCall => pay_gas_for_express_call
         payNativeGasForExpressCall => pay_native_gas_for_express_call
         addGas => add_gas
         addNativeGas => add_native_gas
+        addMultiTokenGas => add_multi_token_gas
         addExpressGas => add_express_gas
         addNativeExpressGas => add_native_express_gas
         collectFees => collect_fees
         refund => refund
-        setGasCollector => set_gas_collector
-        gas_collector => gas_collector
+        batchRefund => batch_refund
+        setGasFee => set_gas_fee
+        removeGasFee => remove_gas_fee
+        setMinGasFeeEnforced => set_min_gas_fee_enforced
+        estimateGasFee => estimate_gas_fee
+        gasFee => gas_fees
+        minGasFeeEnforced => min_gas_fee_enforced
+        outstandingBalance => outstanding_balance
+        totalGasPaid => total_gas_paid
+        totalGasPaidForChain => total_gas_paid_for_chain
+        totalGasRefunded => total_gas_refunded
+        totalGasRefundedForChain => total_gas_refunded_for_chain
+        totalFeesCollected => total_fees_collected
+        totalExpressReimbursed => total_express_reimbursed
+        paymentDestinationChain => payment_destination_chain
+        paymentBalance => payment_balance
+        lastPaymentId => last_payment_id
+        payments => payments
+        expressEscrow => express_escrow
+        paymentExpressCall => payment_express_call
+        registerExpressCall => register_express_call
+        reimburseExpressCall => reimburse_express_call
+        cancelExpressCall => cancel_express_call
+        expressCalls => express_calls
+        setFeeReceivers => set_fee_receivers
+        distributeFees => distribute_fees
+        feeReceivers => fee_receivers
+        setSwapAdapter => set_swap_adapter
+        removeSwapAdapter => remove_swap_adapter
+        getSwapAdapter => swap_adapter
+        addCollector => add_collector
+        removeCollector => remove_collector
+        proposeCollectorship => propose_collectorship
+        acceptCollectorship => accept_collectorship
+        addRefunder => add_refunder
+        removeRefunder => remove_refunder
+        proposeRefundership => propose_refundership
+        acceptRefundership => accept_refundership
+        addExpressRelayer => add_express_relayer
+        removeExpressRelayer => remove_express_relayer
+        isCollector => is_collector
+        isRefunder => is_refunder
+        isExpressRelayer => is_express_relayer
+        getAccountRoles => account_roles
+        getProposedRoles => proposed_roles
     )
 }
 
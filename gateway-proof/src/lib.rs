@@ -0,0 +1,465 @@
+//! Off-chain tooling for building Gateway proofs.
+//!
+//! Computes the same hashes as the Gateway contract, signs them with local ed25519 or secp256k1 keys
+//! and encodes the arguments of the `approveMessages` and `rotateSigners` endpoints.
+//!
+//! Managed types are generic over the API, so the crate doesn't depend on a VM. Rust tests and
+//! interactors can use the `StaticApi` of `multiversx-sc-scenario`.
+
+use core::fmt;
+
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, SignatureError, Signer};
+use k256::ecdsa::signature::hazmat::PrehashSigner;
+use k256::ecdsa::SigningKey;
+use multiversx_sc::api::{
+    ManagedTypeApi, ED25519_KEY_BYTE_LEN, ED25519_SIGNATURE_BYTE_LEN, KECCAK256_RESULT_LEN,
+};
+use multiversx_sc::codec::{dep_encode_to_vec, top_encode_to_vec_u8_or_panic};
+use multiversx_sc::types::{BigUint, ManagedBuffer, ManagedByteArray, ManagedVec};
+use sha3::{Digest, Keccak256};
+
+pub use gateway::constants::{
+    CommandType, CompactProof, Message, Proof, ScheduledRotation, SignerKeyType, WeightedSigner,
+    WeightedSigners, MAX_SIGNERS_BITMAP_LEN, MULTIVERSX_SIGNED_MESSAGE_PREFIX,
+    SECP256K1_KEY_BYTE_LEN, SECP256K1_SIGNATURE_BYTE_LEN,
+};
+
+pub type Hash = [u8; KECCAK256_RESULT_LEN];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofError {
+    // The local signers don't have enough weight to reach the threshold
+    LowSignaturesWeight,
+    // Compact proofs support signer sets of up to 256 signers
+    TooManySigners,
+    // The public key is already part of the signer set
+    DuplicateSigner,
+    // The secp256k1 key could not sign the message hash
+    SigningFailed,
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofError::LowSignaturesWeight => write!(f, "Low signatures weight"),
+            ProofError::TooManySigners => write!(f, "Too many signers"),
+            ProofError::DuplicateSigner => write!(f, "Duplicate signer"),
+            ProofError::SigningFailed => write!(f, "Signing failed"),
+        }
+    }
+}
+
+impl std::error::Error for ProofError {}
+
+pub fn keccak256(data: &[u8]) -> Hash {
+    Keccak256::digest(data).into()
+}
+
+// Same as `get_data_hash` of the Gateway, `data` being the raw endpoint argument (eg. the encoded messages)
+pub fn get_data_hash(command_type: CommandType, data: &[u8]) -> Hash {
+    let mut encoded =
+        dep_encode_to_vec(&command_type).unwrap_or_else(|_| panic!("Could not encode data hash"));
+
+    encoded.extend_from_slice(data);
+
+    keccak256(&encoded)
+}
+
+pub fn message_hash_to_sign(
+    domain_separator: &Hash,
+    signers_hash: &Hash,
+    data_hash: &Hash,
+) -> Hash {
+    let mut encoded =
+        Vec::with_capacity(MULTIVERSX_SIGNED_MESSAGE_PREFIX.len() + 3 * KECCAK256_RESULT_LEN);
+
+    encoded.extend_from_slice(MULTIVERSX_SIGNED_MESSAGE_PREFIX);
+    encoded.extend_from_slice(domain_separator);
+    encoded.extend_from_slice(signers_hash);
+    encoded.extend_from_slice(data_hash);
+
+    keccak256(&encoded)
+}
+
+// Matches the Gateway, which hashes sets made only of ed25519 signers without the key types
+pub fn get_signers_hash<M: ManagedTypeApi>(signers: &WeightedSigners<M>) -> Hash {
+    let all_ed25519 = signers
+        .signers
+        .iter()
+        .all(|signer| signer.key_type == SignerKeyType::Ed25519);
+
+    if !all_ed25519 {
+        let encoded =
+            dep_encode_to_vec(signers).unwrap_or_else(|_| panic!("Could not encode signers"));
+
+        return keccak256(&encoded);
+    }
+
+    let mut encoded = dep_encode_to_vec(&(signers.signers.len() as u32))
+        .unwrap_or_else(|_| panic!("Could not encode signers"));
+
+    for signer in signers.signers.iter() {
+        encoded.extend_from_slice(&signer.signer.to_boxed_bytes().into_vec());
+        encoded.extend(
+            dep_encode_to_vec(&signer.weight)
+                .unwrap_or_else(|_| panic!("Could not encode signers")),
+        );
+    }
+
+    encoded.extend(
+        dep_encode_to_vec(&signers.threshold)
+            .unwrap_or_else(|_| panic!("Could not encode threshold")),
+    );
+    encoded.extend_from_slice(&signers.nonce.to_byte_array());
+
+    keccak256(&encoded)
+}
+
+pub struct Ed25519Signer {
+    keypair: Keypair,
+}
+
+impl Ed25519Signer {
+    pub fn from_secret_key(secret_key: &[u8]) -> Result<Self, SignatureError> {
+        let secret = SecretKey::from_bytes(secret_key)?;
+        let public = PublicKey::from(&secret);
+
+        Ok(Ed25519Signer {
+            keypair: Keypair { secret, public },
+        })
+    }
+
+    pub fn public_key(&self) -> [u8; ED25519_KEY_BYTE_LEN] {
+        self.keypair.public.to_bytes()
+    }
+
+    pub fn sign(&self, message_hash: &Hash) -> [u8; ED25519_SIGNATURE_BYTE_LEN] {
+        self.keypair.sign(message_hash).to_bytes()
+    }
+}
+
+pub struct Secp256k1Signer {
+    signing_key: SigningKey,
+}
+
+impl Secp256k1Signer {
+    pub fn from_secret_key(secret_key: &[u8]) -> Result<Self, k256::ecdsa::Error> {
+        Ok(Secp256k1Signer {
+            signing_key: SigningKey::from_slice(secret_key)?,
+        })
+    }
+
+    // Compressed public key, as expected by the Gateway
+    pub fn public_key(&self) -> [u8; SECP256K1_KEY_BYTE_LEN] {
+        let encoded_point = self.signing_key.verifying_key().to_encoded_point(true);
+
+        let mut public_key = [0u8; SECP256K1_KEY_BYTE_LEN];
+        public_key.copy_from_slice(encoded_point.as_bytes());
+
+        public_key
+    }
+
+    // Signature over the message hash itself, without hashing it again, in the `r || s` format with a low `s`
+    pub fn sign(
+        &self,
+        message_hash: &Hash,
+    ) -> Result<[u8; SECP256K1_SIGNATURE_BYTE_LEN], ProofError> {
+        let signature: k256::ecdsa::Signature = self
+            .signing_key
+            .sign_prehash(message_hash)
+            .map_err(|_| ProofError::SigningFailed)?;
+
+        Ok(signature.to_bytes().into())
+    }
+}
+
+enum LocalSigner {
+    Ed25519(Ed25519Signer),
+    Secp256k1(Secp256k1Signer),
+}
+
+impl LocalSigner {
+    fn sign(&self, message_hash: &Hash) -> Result<Vec<u8>, ProofError> {
+        match self {
+            LocalSigner::Ed25519(signer) => Ok(signer.sign(message_hash).to_vec()),
+            LocalSigner::Secp256k1(signer) => Ok(signer.sign(message_hash)?.to_vec()),
+        }
+    }
+}
+
+struct SetSigner {
+    key_type: SignerKeyType,
+    public_key: Vec<u8>,
+    weight: u128,
+    // Only available for signers whose keys are held locally
+    signer: Option<LocalSigner>,
+}
+
+impl SetSigner {
+    // Gateway order, ed25519 signers first and then ascending by key
+    fn order_key(&self) -> (u8, &[u8]) {
+        (self.key_type as u8, &self.public_key)
+    }
+}
+
+// A set of signers, kept in the order required by the Gateway
+pub struct SignerSet {
+    signers: Vec<SetSigner>,
+    threshold: u128,
+    nonce: Hash,
+}
+
+impl SignerSet {
+    pub fn new(threshold: u128, nonce: Hash) -> Self {
+        SignerSet {
+            signers: Vec::new(),
+            threshold,
+            nonce,
+        }
+    }
+
+    pub fn with_signer(self, signer: Ed25519Signer, weight: u128) -> Result<Self, ProofError> {
+        self.with_set_signer(SetSigner {
+            key_type: SignerKeyType::Ed25519,
+            public_key: signer.public_key().to_vec(),
+            weight,
+            signer: Some(LocalSigner::Ed25519(signer)),
+        })
+    }
+
+    pub fn with_secp256k1_signer(
+        self,
+        signer: Secp256k1Signer,
+        weight: u128,
+    ) -> Result<Self, ProofError> {
+        self.with_set_signer(SetSigner {
+            key_type: SignerKeyType::Secp256k1,
+            public_key: signer.public_key().to_vec(),
+            weight,
+            signer: Some(LocalSigner::Secp256k1(signer)),
+        })
+    }
+
+    // Signer whose key is not held locally, which will never sign proofs built from this set
+    pub fn with_public_key(
+        self,
+        public_key: [u8; ED25519_KEY_BYTE_LEN],
+        weight: u128,
+    ) -> Result<Self, ProofError> {
+        self.with_set_signer(SetSigner {
+            key_type: SignerKeyType::Ed25519,
+            public_key: public_key.to_vec(),
+            weight,
+            signer: None,
+        })
+    }
+
+    pub fn with_secp256k1_public_key(
+        self,
+        public_key: [u8; SECP256K1_KEY_BYTE_LEN],
+        weight: u128,
+    ) -> Result<Self, ProofError> {
+        self.with_set_signer(SetSigner {
+            key_type: SignerKeyType::Secp256k1,
+            public_key: public_key.to_vec(),
+            weight,
+            signer: None,
+        })
+    }
+
+    // The Gateway rejects signer sets containing the same key twice
+    fn with_set_signer(mut self, set_signer: SetSigner) -> Result<Self, ProofError> {
+        let index = self
+            .signers
+            .partition_point(|existing| existing.order_key() < set_signer.order_key());
+
+        if self
+            .signers
+            .get(index)
+            .is_some_and(|existing| existing.order_key() == set_signer.order_key())
+        {
+            return Err(ProofError::DuplicateSigner);
+        }
+
+        self.signers.insert(index, set_signer);
+
+        Ok(self)
+    }
+
+    pub fn weighted_signers<M: ManagedTypeApi>(&self) -> WeightedSigners<M> {
+        let mut signers = ManagedVec::new();
+
+        for set_signer in self.signers.iter() {
+            signers.push(WeightedSigner {
+                key_type: set_signer.key_type,
+                signer: ManagedBuffer::from(&set_signer.public_key[..]),
+                weight: BigUint::from(set_signer.weight),
+            });
+        }
+
+        WeightedSigners {
+            signers,
+            threshold: BigUint::from(self.threshold),
+            nonce: ManagedByteArray::from(&self.nonce),
+        }
+    }
+
+    // Same as `get_signers_hash` of the weighted signers, computed without managed types
+    pub fn signers_hash(&self) -> Hash {
+        let legacy_encoding = self
+            .signers
+            .iter()
+            .all(|set_signer| set_signer.key_type == SignerKeyType::Ed25519);
+
+        let mut encoded = (self.signers.len() as u32).to_be_bytes().to_vec();
+
+        for set_signer in self.signers.iter() {
+            if !legacy_encoding {
+                encoded.push(set_signer.key_type as u8);
+                encoded.extend_from_slice(&(set_signer.public_key.len() as u32).to_be_bytes());
+            }
+
+            encoded.extend_from_slice(&set_signer.public_key);
+            encode_big_uint(&mut encoded, set_signer.weight);
+        }
+
+        encode_big_uint(&mut encoded, self.threshold);
+        encoded.extend_from_slice(&self.nonce);
+
+        keccak256(&encoded)
+    }
+
+    // Signs with local signers in order until the threshold is reached, the Gateway ignores any signatures after that
+    fn sign(&self, message_hash: &Hash) -> Result<Vec<Option<Vec<u8>>>, ProofError> {
+        let mut signatures = Vec::with_capacity(self.signers.len());
+        let mut total_weight = 0u128;
+
+        for set_signer in self.signers.iter() {
+            let signature = match &set_signer.signer {
+                Some(signer) if total_weight < self.threshold => {
+                    total_weight += set_signer.weight;
+
+                    Some(signer.sign(message_hash)?)
+                }
+                _ => None,
+            };
+
+            signatures.push(signature);
+        }
+
+        if total_weight < self.threshold {
+            return Err(ProofError::LowSignaturesWeight);
+        }
+
+        Ok(signatures)
+    }
+
+    pub fn proof<M: ManagedTypeApi>(
+        &self,
+        domain_separator: &Hash,
+        data_hash: &Hash,
+    ) -> Result<Proof<M>, ProofError> {
+        let message_hash = message_hash_to_sign(domain_separator, &self.signers_hash(), data_hash);
+
+        let mut signatures = ManagedVec::new();
+
+        for signature in self.sign(&message_hash)? {
+            signatures.push(signature.map(|signature| ManagedBuffer::from(&signature[..])));
+        }
+
+        Ok(Proof {
+            signers: self.weighted_signers(),
+            signatures,
+        })
+    }
+
+    // For signer sets already stored by the Gateway, referenced by their epoch
+    pub fn compact_proof<M: ManagedTypeApi>(
+        &self,
+        epoch: u64,
+        domain_separator: &Hash,
+        data_hash: &Hash,
+    ) -> Result<CompactProof<M>, ProofError> {
+        if self.signers.len() > MAX_SIGNERS_BITMAP_LEN * 8 {
+            return Err(ProofError::TooManySigners);
+        }
+
+        let message_hash = message_hash_to_sign(domain_separator, &self.signers_hash(), data_hash);
+
+        let mut signers_bitmap = vec![0u8; self.signers.len().div_ceil(8)];
+        let mut signatures = ManagedVec::new();
+
+        for (index, signature) in self.sign(&message_hash)?.into_iter().enumerate() {
+            if let Some(signature) = signature {
+                signers_bitmap[index / 8] |= 1 << (index % 8);
+                signatures.push(ManagedBuffer::from(&signature[..]));
+            }
+        }
+
+        Ok(CompactProof {
+            epoch: BigUint::from(epoch),
+            signers_bitmap: ManagedBuffer::from(&signers_bitmap[..]),
+            signatures,
+        })
+    }
+
+    // Top encoded `messages` and `proof` arguments, signed for the given domain separator. The optional
+    // domain separator argument only needs to be appended if it is the previous one
+    pub fn approve_messages_args<M: ManagedTypeApi>(
+        &self,
+        domain_separator: &Hash,
+        messages: &ManagedVec<M, Message<M>>,
+    ) -> Result<Vec<Vec<u8>>, ProofError> {
+        let messages = top_encode_to_vec_u8_or_panic(messages);
+
+        let data_hash = get_data_hash(CommandType::ApproveMessages, &messages);
+        let proof = self.proof::<M>(domain_separator, &data_hash)?;
+
+        Ok(vec![messages, top_encode_to_vec_u8_or_panic(&proof)])
+    }
+
+    // Top encoded `new_signers` and `proof` arguments, with the proof signed by this signer set
+    pub fn rotate_signers_args<M: ManagedTypeApi>(
+        &self,
+        domain_separator: &Hash,
+        new_signers: &SignerSet,
+    ) -> Result<Vec<Vec<u8>>, ProofError> {
+        let new_signers = top_encode_to_vec_u8_or_panic(&new_signers.weighted_signers::<M>());
+
+        let data_hash = get_data_hash(CommandType::RotateSigners, &new_signers);
+        let proof = self.proof::<M>(domain_separator, &data_hash)?;
+
+        Ok(vec![new_signers, top_encode_to_vec_u8_or_panic(&proof)])
+    }
+
+    // Top encoded `proof` argument of `cancelScheduledRotationWithProof`, signed over the scheduled rotation
+    // to `new_signers` with the given eta
+    pub fn cancel_scheduled_rotation_args<M: ManagedTypeApi>(
+        &self,
+        domain_separator: &Hash,
+        new_signers: &SignerSet,
+        eta: u64,
+    ) -> Result<Vec<Vec<u8>>, ProofError> {
+        let scheduled_rotation = top_encode_to_vec_u8_or_panic(&ScheduledRotation::<M> {
+            new_signers: new_signers.weighted_signers(),
+            eta,
+        });
+
+        let data_hash = get_data_hash(CommandType::CancelRotateSigners, &scheduled_rotation);
+        let proof = self.proof::<M>(domain_separator, &data_hash)?;
+
+        Ok(vec![top_encode_to_vec_u8_or_panic(&proof)])
+    }
+}
+
+// Nested encoding of a `BigUint`, its minimal big endian bytes prefixed by their length
+fn encode_big_uint(encoded: &mut Vec<u8>, value: u128) {
+    let bytes = value.to_be_bytes();
+    let first_non_zero = bytes
+        .iter()
+        .position(|byte| *byte != 0)
+        .unwrap_or(bytes.len());
+
+    encoded.extend_from_slice(&((bytes.len() - first_non_zero) as u32).to_be_bytes());
+    encoded.extend_from_slice(&bytes[first_non_zero..]);
+}
@@ -0,0 +1,347 @@
+use ed25519_dalek::{PublicKey, Signature, Verifier};
+use k256::ecdsa::signature::hazmat::PrehashVerifier;
+use k256::ecdsa::VerifyingKey;
+use multiversx_sc::codec::TopDecode;
+use multiversx_sc::types::{BigUint, ManagedAddress, ManagedBuffer, ManagedByteArray, ManagedVec};
+use multiversx_sc_scenario::api::StaticApi;
+
+use gateway_proof::{
+    get_data_hash, get_signers_hash, keccak256, message_hash_to_sign, CommandType, Ed25519Signer,
+    Hash, Message, Proof, ProofError, Secp256k1Signer, SignerKeyType, SignerSet, WeightedSigners,
+};
+
+const DOMAIN_SEPARATOR: Hash = [0x11; 32];
+const NONCE: Hash = [0x22; 32];
+
+fn signer(seed: u8) -> Ed25519Signer {
+    Ed25519Signer::from_secret_key(&[seed; 32]).unwrap()
+}
+
+fn secp256k1_signer(seed: u8) -> Secp256k1Signer {
+    Secp256k1Signer::from_secret_key(&[seed; 32]).unwrap()
+}
+
+fn signer_set() -> SignerSet {
+    SignerSet::new(10, NONCE)
+        .with_signer(signer(1), 5)
+        .and_then(|signer_set| signer_set.with_signer(signer(2), 5))
+        .and_then(|signer_set| signer_set.with_signer(signer(3), 5))
+        .unwrap()
+}
+
+fn messages() -> ManagedVec<StaticApi, Message<StaticApi>> {
+    let mut messages = ManagedVec::new();
+
+    messages.push(Message {
+        source_chain: ManagedBuffer::from("ethereum"),
+        message_id: ManagedBuffer::from("messageId"),
+        source_address: ManagedBuffer::from("0x4976da71bF84D750b5451B053051158EC0A4E876"),
+        contract_address: ManagedAddress::from(&[0x33; 32]),
+        payload_hash: ManagedByteArray::from(&[0x44; 32]),
+    });
+
+    messages
+}
+
+fn verify(
+    public_key: &ManagedBuffer<StaticApi>,
+    message_hash: &Hash,
+    signature: &ManagedBuffer<StaticApi>,
+) -> bool {
+    let signature = Signature::from_bytes(signature.to_boxed_bytes().as_slice()).unwrap();
+
+    PublicKey::from_bytes(public_key.to_boxed_bytes().as_slice())
+        .unwrap()
+        .verify(message_hash, &signature)
+        .is_ok()
+}
+
+#[test]
+fn data_hash() {
+    assert_eq!(
+        get_data_hash(CommandType::ApproveMessages, b"data"),
+        keccak256(b"\x00data")
+    );
+    assert_eq!(
+        get_data_hash(CommandType::RotateSigners, b"data"),
+        keccak256(b"\x01data")
+    );
+}
+
+#[test]
+fn message_hash() {
+    let mut expected = b"\x19MultiversX Signed Message:\n".to_vec();
+    expected.extend_from_slice(&[0x11; 32]);
+    expected.extend_from_slice(&[0x55; 32]);
+    expected.extend_from_slice(&[0x66; 32]);
+
+    assert_eq!(
+        message_hash_to_sign(&DOMAIN_SEPARATOR, &[0x55; 32], &[0x66; 32]),
+        keccak256(&expected)
+    );
+}
+
+#[test]
+fn signers_ordered_by_key() {
+    let weighted_signers = signer_set().weighted_signers::<StaticApi>();
+
+    let keys: Vec<_> = weighted_signers
+        .signers
+        .iter()
+        .map(|weighted_signer| weighted_signer.signer.to_boxed_bytes().into_vec())
+        .collect();
+
+    let mut sorted_keys = keys.clone();
+    sorted_keys.sort();
+
+    assert_eq!(keys.len(), 3);
+    assert_eq!(keys, sorted_keys);
+    assert_eq!(weighted_signers.threshold, BigUint::from(10u64));
+}
+
+#[test]
+fn signers_hash() {
+    let signer_set = SignerSet::new(1, NONCE)
+        .with_public_key([0x77; 32], 1)
+        .unwrap();
+
+    // Sets of ed25519 signers are hashed without key types and key lengths
+    let mut expected = vec![0, 0, 0, 1]; // signers length
+    expected.extend_from_slice(&[0x77; 32]);
+    expected.extend_from_slice(&[0, 0, 0, 1, 1]); // weight
+    expected.extend_from_slice(&[0, 0, 0, 1, 1]); // threshold
+    expected.extend_from_slice(&NONCE);
+
+    assert_eq!(signer_set.signers_hash(), keccak256(&expected));
+}
+
+#[test]
+fn proof_signed_until_threshold() {
+    let signer_set = signer_set();
+    let data_hash = [0x66; 32];
+
+    let proof = signer_set
+        .proof::<StaticApi>(&DOMAIN_SEPARATOR, &data_hash)
+        .unwrap();
+
+    let message_hash =
+        message_hash_to_sign(&DOMAIN_SEPARATOR, &signer_set.signers_hash(), &data_hash);
+
+    let signatures: Vec<_> = proof.signatures.iter().collect();
+    assert_eq!(signatures.len(), 3);
+    assert!(signatures[2].is_none());
+
+    for (index, signature) in signatures.iter().take(2).enumerate() {
+        let signer = proof.signers.signers.get(index);
+
+        assert!(verify(
+            &signer.signer,
+            &message_hash,
+            signature.as_ref().unwrap(),
+        ));
+    }
+}
+
+#[test]
+fn proof_low_signatures_weight() {
+    let signer_set = SignerSet::new(10, NONCE)
+        .with_signer(signer(1), 5)
+        .and_then(|signer_set| signer_set.with_public_key(signer(2).public_key(), 5))
+        .unwrap();
+
+    assert_eq!(
+        signer_set
+            .proof::<StaticApi>(&DOMAIN_SEPARATOR, &[0x66; 32])
+            .err(),
+        Some(ProofError::LowSignaturesWeight)
+    );
+}
+
+#[test]
+fn compact_proof() {
+    let signer_set = SignerSet::new(10, NONCE)
+        .with_public_key([0x00; 32], 5)
+        .and_then(|signer_set| signer_set.with_signer(signer(1), 5))
+        .and_then(|signer_set| signer_set.with_signer(signer(2), 5))
+        .unwrap();
+
+    let proof = signer_set
+        .compact_proof::<StaticApi>(2, &DOMAIN_SEPARATOR, &[0x66; 32])
+        .unwrap();
+
+    assert_eq!(proof.epoch, BigUint::from(2u64));
+    assert_eq!(proof.signers_bitmap, ManagedBuffer::from(&[0b110u8][..]));
+    assert_eq!(proof.signatures.len(), 2);
+}
+
+#[test]
+fn approve_messages_args() {
+    let signer_set = signer_set();
+
+    let args = signer_set
+        .approve_messages_args(&DOMAIN_SEPARATOR, &messages())
+        .unwrap();
+
+    assert_eq!(args.len(), 2);
+
+    let proof = Proof::<StaticApi>::top_decode(args[1].as_slice()).unwrap();
+    assert_eq!(get_signers_hash(&proof.signers), signer_set.signers_hash());
+
+    let message_hash = message_hash_to_sign(
+        &DOMAIN_SEPARATOR,
+        &signer_set.signers_hash(),
+        &get_data_hash(CommandType::ApproveMessages, &args[0]),
+    );
+
+    let signature = proof.signatures.get(0).unwrap();
+    assert!(verify(
+        &proof.signers.signers.get(0).signer,
+        &message_hash,
+        &signature,
+    ));
+}
+
+#[test]
+fn rotate_signers_args() {
+    let signer_set = signer_set();
+    let new_signer_set = SignerSet::new(1, [0x88; 32])
+        .with_signer(signer(4), 1)
+        .unwrap();
+
+    let args = signer_set
+        .rotate_signers_args::<StaticApi>(&DOMAIN_SEPARATOR, &new_signer_set)
+        .unwrap();
+
+    let new_signers = WeightedSigners::<StaticApi>::top_decode(args[0].as_slice()).unwrap();
+    assert_eq!(
+        get_signers_hash(&new_signers),
+        new_signer_set.signers_hash()
+    );
+
+    let proof = Proof::<StaticApi>::top_decode(args[1].as_slice()).unwrap();
+    let message_hash = message_hash_to_sign(
+        &DOMAIN_SEPARATOR,
+        &signer_set.signers_hash(),
+        &get_data_hash(CommandType::RotateSigners, &args[0]),
+    );
+
+    assert!(verify(
+        &proof.signers.signers.get(0).signer,
+        &message_hash,
+        &proof.signatures.get(0).unwrap(),
+    ));
+}
+
+#[test]
+fn duplicate_signers() {
+    assert_eq!(
+        SignerSet::new(10, NONCE)
+            .with_signer(signer(1), 5)
+            .and_then(|signer_set| signer_set.with_public_key(signer(1).public_key(), 5))
+            .err(),
+        Some(ProofError::DuplicateSigner)
+    );
+    assert_eq!(
+        SignerSet::new(10, NONCE)
+            .with_secp256k1_public_key(secp256k1_signer(1).public_key(), 5)
+            .and_then(|signer_set| signer_set.with_secp256k1_signer(secp256k1_signer(1), 5))
+            .err(),
+        Some(ProofError::DuplicateSigner)
+    );
+}
+
+#[test]
+fn mixed_signers_ordered_by_key_type() {
+    let signer_set = SignerSet::new(10, NONCE)
+        .with_secp256k1_signer(secp256k1_signer(1), 5)
+        .and_then(|signer_set| signer_set.with_signer(signer(2), 5))
+        .and_then(|signer_set| signer_set.with_public_key([0xff; 32], 5))
+        .unwrap();
+
+    let weighted_signers = signer_set.weighted_signers::<StaticApi>();
+
+    let key_types: Vec<_> = weighted_signers
+        .signers
+        .iter()
+        .map(|weighted_signer| weighted_signer.key_type)
+        .collect();
+
+    assert!(
+        key_types
+            == vec![
+                SignerKeyType::Ed25519,
+                SignerKeyType::Ed25519,
+                SignerKeyType::Secp256k1
+            ]
+    );
+    assert_eq!(
+        weighted_signers
+            .signers
+            .get(1)
+            .signer
+            .to_boxed_bytes()
+            .as_slice(),
+        &[0xff; 32]
+    );
+    assert_eq!(weighted_signers.signers.get(2).signer.len(), 33);
+}
+
+#[test]
+fn mixed_signers_hash() {
+    let public_key = secp256k1_signer(1).public_key();
+    let signer_set = SignerSet::new(1, NONCE)
+        .with_public_key([0x77; 32], 1)
+        .and_then(|signer_set| signer_set.with_secp256k1_public_key(public_key, 1))
+        .unwrap();
+
+    // Sets containing secp256k1 signers are hashed with the key types and key lengths
+    let mut expected = vec![0, 0, 0, 2]; // signers length
+    expected.push(0); // Ed25519 key type
+    expected.extend_from_slice(&[0, 0, 0, 32]);
+    expected.extend_from_slice(&[0x77; 32]);
+    expected.extend_from_slice(&[0, 0, 0, 1, 1]); // weight
+    expected.push(1); // Secp256k1 key type
+    expected.extend_from_slice(&[0, 0, 0, 33]);
+    expected.extend_from_slice(&public_key);
+    expected.extend_from_slice(&[0, 0, 0, 1, 1]); // weight
+    expected.extend_from_slice(&[0, 0, 0, 1, 1]); // threshold
+    expected.extend_from_slice(&NONCE);
+
+    assert_eq!(signer_set.signers_hash(), keccak256(&expected));
+    assert_eq!(
+        get_signers_hash(&signer_set.weighted_signers::<StaticApi>()),
+        keccak256(&expected)
+    );
+}
+
+#[test]
+fn secp256k1_proof() {
+    let signer_set = SignerSet::new(10, NONCE)
+        .with_secp256k1_signer(secp256k1_signer(1), 5)
+        .and_then(|signer_set| signer_set.with_secp256k1_signer(secp256k1_signer(2), 5))
+        .unwrap();
+    let data_hash = [0x66; 32];
+
+    let proof = signer_set
+        .proof::<StaticApi>(&DOMAIN_SEPARATOR, &data_hash)
+        .unwrap();
+
+    let message_hash =
+        message_hash_to_sign(&DOMAIN_SEPARATOR, &signer_set.signers_hash(), &data_hash);
+
+    for (index, signature) in proof.signatures.iter().enumerate() {
+        let public_key = proof.signers.signers.get(index).signer.to_boxed_bytes();
+        let signature = signature.as_ref().unwrap().to_boxed_bytes();
+
+        // Signature over the message hash itself in the `r || s` format, as verified by the Gateway
+        assert_eq!(signature.len(), 64);
+
+        let verifying_key = VerifyingKey::from_sec1_bytes(public_key.as_slice()).unwrap();
+        let signature = k256::ecdsa::Signature::from_slice(signature.as_slice()).unwrap();
+
+        assert!(signature.normalize_s().is_none());
+        assert!(verifying_key
+            .verify_prehash(&message_hash, &signature)
+            .is_ok());
+    }
+}
@@ -1,6 +1,11 @@
-use multiversx_sc::api::{ED25519_SIGNATURE_BYTE_LEN, KECCAK256_RESULT_LEN};
-
-use crate::constants::{Proof, WeightedSigners, MULTIVERSX_SIGNED_MESSAGE_PREFIX};
+use multiversx_sc::api::KECCAK256_RESULT_LEN;
+
+use crate::constants::{
+    encode_secp256k1_der_signature, CompactProof, Proof, ScheduledRotation, SignerKeyType,
+    WeightedSigners, MAX_DOMAIN_SEPARATOR_GRACE_PERIOD, MAX_SIGNERS_BITMAP_LEN,
+    MULTIVERSX_SIGNED_MESSAGE_PREFIX, SECP256K1_RECOVERABLE_SIGNATURE_BYTE_LEN,
+    SECP256K1_SIGNATURE_BYTE_LEN,
+};
 use crate::events;
 
 multiversx_sc::imports!();
@@ -9,31 +14,55 @@ multiversx_sc::imports!();
 pub trait AuthModule: events::Events {
     /// Integration Functions
 
+    // The domain separator the proof was signed with only needs to be provided if it is the previous one
     #[view(validateProof)]
     fn validate_proof(
         &self,
         data_hash: ManagedByteArray<KECCAK256_RESULT_LEN>,
         proof: Proof<Self::Api>,
+        domain_separator: OptionalValue<ManagedByteArray<KECCAK256_RESULT_LEN>>,
     ) -> bool {
-        let signers = proof.signers;
-
-        let signers_hash = self.get_signers_hash(&signers);
+        let signers_hash = self.get_signers_hash(&proof.signers);
         let signer_epoch = self.epoch_by_signer_hash(&signers_hash).get();
-        let current_epoch = self.epoch().get();
 
-        let is_latest_signers = signer_epoch == current_epoch;
+        self.validate_proof_raw(
+            data_hash,
+            signer_epoch,
+            signers_hash,
+            proof.signers,
+            proof.signatures,
+            domain_separator,
+        )
+    }
 
-        require!(
-            signer_epoch > 0
-                && current_epoch - signer_epoch <= self.previous_signers_retention().get(),
-            "Invalid signers"
-        );
+    #[view(validateCompactProof)]
+    fn validate_compact_proof(
+        &self,
+        data_hash: ManagedByteArray<KECCAK256_RESULT_LEN>,
+        proof: CompactProof<Self::Api>,
+        domain_separator: OptionalValue<ManagedByteArray<KECCAK256_RESULT_LEN>>,
+    ) -> bool {
+        let signers_mapper = self.signers_by_epoch(&proof.epoch);
 
-        let message_hash = self.message_hash_to_sign(&signers_hash, &data_hash);
+        require!(!signers_mapper.is_empty(), "Invalid signers");
 
-        self.validate_signatures(message_hash, signers, proof.signatures);
+        let signers = signers_mapper.get();
+        let signers_hash = self.signer_hash_by_epoch(&proof.epoch).get();
 
-        is_latest_signers
+        let signatures = self.expand_signatures(
+            signers.signers.len(),
+            &proof.signers_bitmap,
+            proof.signatures,
+        );
+
+        self.validate_proof_raw(
+            data_hash,
+            proof.epoch,
+            signers_hash,
+            signers,
+            signatures,
+            domain_separator,
+        )
     }
 
     fn rotate_signers_raw(
@@ -53,6 +82,7 @@ pub trait AuthModule: events::Events {
         });
 
         self.signer_hash_by_epoch(&new_epoch).set(&new_signers_hash);
+        self.signers_by_epoch(&new_epoch).set(&new_signers);
 
         let epoch_for_hash_mapper = self.epoch_by_signer_hash(&new_signers_hash);
 
@@ -60,11 +90,231 @@ pub trait AuthModule: events::Events {
 
         epoch_for_hash_mapper.set(&new_epoch);
 
+        // A rotation to the same signers scheduled before could never be executed anymore
+        let scheduled_rotation_mapper = self.scheduled_rotation();
+        if !scheduled_rotation_mapper.is_empty()
+            && self.get_signers_hash(&scheduled_rotation_mapper.get().new_signers)
+                == new_signers_hash
+        {
+            scheduled_rotation_mapper.clear();
+
+            self.signers_rotation_cancelled_event(new_signers_hash.clone());
+        }
+
         self.signers_rotated_event(new_epoch, new_signers_hash, new_signers);
     }
 
+    fn schedule_rotation_raw(&self, new_signers: WeightedSigners<Self::Api>) {
+        let scheduled_rotation_mapper = self.scheduled_rotation();
+
+        require!(
+            scheduled_rotation_mapper.is_empty(),
+            "Rotation already scheduled"
+        );
+
+        self.validate_signers(&new_signers);
+
+        let new_signers_hash = self.get_signers_hash(&new_signers);
+
+        // Signers rotated in before could never be rotated in again, so the rotation would never execute
+        require!(
+            self.epoch_by_signer_hash(&new_signers_hash).is_empty(),
+            "Duplicate signers"
+        );
+
+        let eta = self.blockchain().get_block_timestamp() + self.minimum_rotation_delay().get();
+
+        self.signers_rotation_scheduled_event(new_signers_hash, eta);
+
+        scheduled_rotation_mapper.set(ScheduledRotation { new_signers, eta });
+    }
+
+    fn execute_scheduled_rotation_raw(&self) {
+        let scheduled_rotation_mapper = self.scheduled_rotation();
+
+        require!(
+            !scheduled_rotation_mapper.is_empty(),
+            "No scheduled rotation"
+        );
+
+        let scheduled_rotation = scheduled_rotation_mapper.take();
+
+        require!(
+            self.blockchain().get_block_timestamp() >= scheduled_rotation.eta,
+            "Rotation not ready"
+        );
+
+        // The rotation delay was already enforced by the eta
+        self.rotate_signers_raw(scheduled_rotation.new_signers, false);
+    }
+
+    fn cancel_scheduled_rotation_raw(&self) {
+        let scheduled_rotation_mapper = self.scheduled_rotation();
+
+        require!(
+            !scheduled_rotation_mapper.is_empty(),
+            "No scheduled rotation"
+        );
+
+        let scheduled_rotation = scheduled_rotation_mapper.take();
+
+        self.signers_rotation_cancelled_event(
+            self.get_signers_hash(&scheduled_rotation.new_signers),
+        );
+    }
+
+    fn rotate_domain_separator_raw(
+        &self,
+        new_domain_separator: ManagedByteArray<KECCAK256_RESULT_LEN>,
+        grace_period: u64,
+    ) {
+        let previous_domain_separator = self.domain_separator().get();
+
+        require!(
+            new_domain_separator != previous_domain_separator,
+            "Invalid domain separator"
+        );
+
+        require!(
+            grace_period <= MAX_DOMAIN_SEPARATOR_GRACE_PERIOD,
+            "Invalid grace period"
+        );
+
+        let grace_period_end = self.blockchain().get_block_timestamp() + grace_period;
+
+        if grace_period > 0 {
+            self.previous_domain_separator()
+                .set(&previous_domain_separator);
+            self.previous_domain_separator_expiry()
+                .set(grace_period_end);
+        } else {
+            self.previous_domain_separator().clear();
+            self.previous_domain_separator_expiry().clear();
+        }
+
+        self.domain_separator().set(&new_domain_separator);
+
+        self.domain_separator_rotated_event(
+            &new_domain_separator,
+            &previous_domain_separator,
+            grace_period_end,
+        );
+    }
+
+    fn revoke_signers_raw(&self, epoch: BigUint) {
+        let signers_hash_mapper = self.signer_hash_by_epoch(&epoch);
+
+        require!(!signers_hash_mapper.is_empty(), "Invalid epoch");
+
+        // Revoking the latest signers halts the Gateway until they are unrevoked or new signers are set,
+        // so it is reserved to the owner (governance)
+        require!(
+            epoch != self.epoch().get()
+                || self.blockchain().get_caller() == self.blockchain().get_owner_address(),
+            "Can not revoke latest signers"
+        );
+        require!(
+            self.revoked_epochs().insert(epoch.clone()),
+            "Signers already revoked"
+        );
+
+        self.signers_revoked_event(epoch, signers_hash_mapper.get());
+    }
+
+    fn unrevoke_signers_raw(&self, epoch: BigUint) {
+        require!(
+            self.revoked_epochs().swap_remove(&epoch),
+            "Signers not revoked"
+        );
+
+        let signers_hash = self.signer_hash_by_epoch(&epoch).get();
+
+        self.signers_unrevoked_event(epoch, signers_hash);
+    }
+
     /// Internal Functions
 
+    fn validate_proof_raw(
+        &self,
+        data_hash: ManagedByteArray<KECCAK256_RESULT_LEN>,
+        signer_epoch: BigUint,
+        signers_hash: ManagedByteArray<KECCAK256_RESULT_LEN>,
+        signers: WeightedSigners<Self::Api>,
+        signatures: ManagedVec<Option<ManagedBuffer>>,
+        domain_separator: OptionalValue<ManagedByteArray<KECCAK256_RESULT_LEN>>,
+    ) -> bool {
+        let current_epoch = self.epoch().get();
+
+        let is_latest_signers = signer_epoch == current_epoch;
+
+        require!(
+            signer_epoch > 0
+                && &current_epoch - &signer_epoch <= self.previous_signers_retention().get(),
+            "Invalid signers"
+        );
+        require!(
+            !self.revoked_epochs().contains(&signer_epoch),
+            "Revoked signers"
+        );
+
+        let domain_separator = self.get_domain_separator(domain_separator);
+
+        let message_hash = self.message_hash_to_sign(&domain_separator, &signers_hash, &data_hash);
+
+        self.validate_signatures(message_hash, signers, signatures);
+
+        is_latest_signers
+    }
+
+    // Converts the signers bitmap and the signatures of a compact proof to the signatures format of a full proof
+    fn expand_signatures(
+        &self,
+        signers_len: usize,
+        signers_bitmap: &ManagedBuffer,
+        signatures: ManagedVec<ManagedBuffer>,
+    ) -> ManagedVec<Option<ManagedBuffer>> {
+        let bitmap_len = signers_len.div_ceil(8);
+
+        require!(
+            bitmap_len <= MAX_SIGNERS_BITMAP_LEN && signers_bitmap.len() == bitmap_len,
+            "Invalid signers bitmap"
+        );
+
+        let mut bitmap = [0u8; MAX_SIGNERS_BITMAP_LEN];
+
+        let _ = signers_bitmap.load_slice(0, &mut bitmap[..bitmap_len]);
+
+        let mut expanded = ManagedVec::new();
+        let mut signatures_iter = signatures.into_iter();
+
+        for bit_index in 0..bitmap_len * 8 {
+            let is_set = bitmap[bit_index / 8] & (1u8 << (bit_index % 8)) != 0;
+
+            if bit_index >= signers_len {
+                // Unused bits of the last byte need to be unset
+                require!(!is_set, "Invalid signers bitmap");
+
+                continue;
+            }
+
+            if !is_set {
+                expanded.push(None);
+
+                continue;
+            }
+
+            let signature = signatures_iter
+                .next()
+                .unwrap_or_else(|| sc_panic!("Invalid signatures"));
+
+            expanded.push(Some(signature));
+        }
+
+        require!(signatures_iter.next().is_none(), "Invalid signatures");
+
+        expanded
+    }
+
     fn update_rotation_timestamp(&self, enforce_rotation_delay: bool) {
         let last_rotation_timestamp_mapper = self.last_rotation_timestamp();
         let last_rotation_timestamp = last_rotation_timestamp_mapper.get();
@@ -83,11 +333,12 @@ pub trait AuthModule: events::Events {
     // Signatures need to have the same length as signers, but some signers could have not signed
     // the message hash, so for those signers we have the signature None instead.
     // Signers are ordered and the signatures will also need to be in the same order
+    // Each signature is verified according to the key type of its signer, so mixed signer sets are supported
     fn validate_signatures(
         &self,
         message_hash: ManagedByteArray<KECCAK256_RESULT_LEN>,
         weighted_signers: WeightedSigners<Self::Api>,
-        signatures: ManagedVec<Option<ManagedByteArray<ED25519_SIGNATURE_BYTE_LEN>>>,
+        signatures: ManagedVec<Option<ManagedBuffer>>,
     ) {
         let signers = weighted_signers.signers;
 
@@ -105,10 +356,11 @@ pub trait AuthModule: events::Events {
 
             let signer = signers.get(signer_index);
 
-            self.crypto().verify_ed25519(
-                signer.signer.as_managed_buffer(),
-                message_hash.as_managed_buffer(),
-                signature.unwrap().as_managed_buffer(),
+            self.verify_signature(
+                signer.key_type,
+                &signer.signer,
+                &message_hash,
+                &signature.unwrap(),
             );
 
             total_weight += signer.weight;
@@ -121,15 +373,81 @@ pub trait AuthModule: events::Events {
         sc_panic!("Low signatures weight");
     }
 
+    fn verify_signature(
+        &self,
+        key_type: SignerKeyType,
+        key: &ManagedBuffer,
+        message_hash: &ManagedByteArray<KECCAK256_RESULT_LEN>,
+        signature: &ManagedBuffer,
+    ) {
+        match key_type {
+            SignerKeyType::Ed25519 => {
+                self.crypto()
+                    .verify_ed25519(key, message_hash.as_managed_buffer(), signature);
+            }
+            SignerKeyType::Secp256k1 => {
+                let signature_len = signature.len();
+
+                require!(
+                    signature_len == SECP256K1_SIGNATURE_BYTE_LEN
+                        || signature_len == SECP256K1_RECOVERABLE_SIGNATURE_BYTE_LEN,
+                    "Invalid signature"
+                );
+
+                let der_signature = encode_secp256k1_der_signature(signature);
+
+                // The message hash is signed directly, without being hashed again
+                require!(
+                    self.crypto().verify_custom_secp256k1(
+                        key,
+                        message_hash.as_managed_buffer(),
+                        &der_signature,
+                        MessageHashType::ECDSAPlainMsg,
+                    ),
+                    "Invalid signature"
+                );
+            }
+        }
+    }
+
+    // Signatures can not be checked against both domain separators, since an invalid ed25519 signature
+    // stops the execution, so the previous domain separator needs to be explicitly provided
+    fn get_domain_separator(
+        &self,
+        domain_separator: OptionalValue<ManagedByteArray<KECCAK256_RESULT_LEN>>,
+    ) -> ManagedByteArray<KECCAK256_RESULT_LEN> {
+        let current_domain_separator = self.domain_separator().get();
+
+        let domain_separator = match domain_separator.into_option() {
+            None => return current_domain_separator,
+            Some(domain_separator) => domain_separator,
+        };
+
+        if domain_separator == current_domain_separator {
+            return domain_separator;
+        }
+
+        require!(
+            !self.previous_domain_separator().is_empty()
+                && domain_separator == self.previous_domain_separator().get()
+                && self.blockchain().get_block_timestamp()
+                    < self.previous_domain_separator_expiry().get(),
+            "Invalid domain separator"
+        );
+
+        domain_separator
+    }
+
     fn message_hash_to_sign(
         &self,
+        domain_separator: &ManagedByteArray<KECCAK256_RESULT_LEN>,
         signers_hash: &ManagedByteArray<KECCAK256_RESULT_LEN>,
         data_hash: &ManagedByteArray<KECCAK256_RESULT_LEN>,
     ) -> ManagedByteArray<KECCAK256_RESULT_LEN> {
         let mut encoded = ManagedBuffer::new();
 
         encoded.append(&ManagedBuffer::from(MULTIVERSX_SIGNED_MESSAGE_PREFIX));
-        encoded.append(self.domain_separator().get().as_managed_buffer());
+        encoded.append(domain_separator.as_managed_buffer());
         encoded.append(signers_hash.as_managed_buffer());
         encoded.append(data_hash.as_managed_buffer());
 
@@ -142,14 +460,27 @@ pub trait AuthModule: events::Events {
         require!(!signers.is_empty(), "Invalid signers");
 
         let mut total_weight = BigUint::zero();
+        let mut prev_key_type = SignerKeyType::Ed25519;
         let mut prev_signer = BigUint::zero();
 
+        // Signers are ordered by key type first and then by key
         for weighted_signer in signers.into_iter() {
-            let curr_signer =
-                BigUint::from_bytes_be_buffer(weighted_signer.signer.as_managed_buffer());
+            let curr_key_type = weighted_signer.key_type;
+
+            require!(
+                weighted_signer.signer.len() == curr_key_type.key_length(),
+                "Invalid signers"
+            );
 
-            require!(curr_signer > prev_signer, "Invalid signers");
+            let curr_signer = BigUint::from_bytes_be_buffer(&weighted_signer.signer);
 
+            require!(
+                curr_key_type > prev_key_type
+                    || (curr_key_type == prev_key_type && curr_signer > prev_signer),
+                "Invalid signers"
+            );
+
+            prev_key_type = curr_key_type;
             prev_signer = curr_signer;
 
             require!(weighted_signer.weight > 0, "Invalid weights");
@@ -169,19 +500,113 @@ pub trait AuthModule: events::Events {
     ) -> ManagedByteArray<KECCAK256_RESULT_LEN> {
         let mut encoded = ManagedBuffer::new();
 
-        signers.signers
-            .dep_encode(&mut encoded)
-            .unwrap_or_else(|_| sc_panic!("Could not encode signers"));
-        signers.threshold
+        // Sets made only of ed25519 signers keep the encoding from before secp256k1 signers were supported,
+        // with the raw 32 bytes key and no key type, so the hashes of signers rotated before an upgrade still match
+        if signers
+            .signers
+            .iter()
+            .all(|signer| signer.key_type == SignerKeyType::Ed25519)
+        {
+            signers
+                .signers
+                .len()
+                .dep_encode(&mut encoded)
+                .unwrap_or_else(|_| sc_panic!("Could not encode signers"));
+
+            for signer in signers.signers.iter() {
+                require!(
+                    signer.signer.len() == SignerKeyType::Ed25519.key_length(),
+                    "Invalid signers"
+                );
+
+                encoded.append(&signer.signer);
+                signer
+                    .weight
+                    .dep_encode(&mut encoded)
+                    .unwrap_or_else(|_| sc_panic!("Could not encode signers"));
+            }
+        } else {
+            signers
+                .signers
+                .dep_encode(&mut encoded)
+                .unwrap_or_else(|_| sc_panic!("Could not encode signers"));
+        }
+        signers
+            .threshold
             .dep_encode(&mut encoded)
             .unwrap_or_else(|_| sc_panic!("Could not encode threshold"));
-        signers.nonce
+        signers
+            .nonce
             .dep_encode(&mut encoded)
             .unwrap_or_else(|_| sc_panic!("Could not encode nonce"));
 
         self.crypto().keccak256(encoded)
     }
 
+    // Signers rotated before signers started to be stored on chain can be stored by anyone,
+    // since they are checked against the stored signers hash
+    #[endpoint(storeSigners)]
+    fn store_signers(&self, signers: WeightedSigners<Self::Api>) {
+        let signers_hash = self.get_signers_hash(&signers);
+        let epoch_mapper = self.epoch_by_signer_hash(&signers_hash);
+
+        require!(!epoch_mapper.is_empty(), "Invalid signers");
+
+        let signers_mapper = self.signers_by_epoch(&epoch_mapper.get());
+
+        require!(signers_mapper.is_empty(), "Signers already stored");
+
+        signers_mapper.set(signers);
+    }
+
+    #[view(currentSigners)]
+    fn current_signers(&self) -> WeightedSigners<Self::Api> {
+        self.get_stored_signers(&self.epoch().get())
+    }
+
+    // Returns the first and last epochs of the signers which are still valid, not taking into account revoked signers.
+    // Nothing is returned if no signers were rotated yet
+    #[view(validEpochs)]
+    fn valid_epochs(&self) -> OptionalValue<MultiValue2<BigUint, BigUint>> {
+        let current_epoch = self.epoch().get();
+
+        if current_epoch == 0 {
+            return OptionalValue::None;
+        }
+
+        let retention = self.previous_signers_retention().get();
+
+        let first_epoch = if current_epoch > retention {
+            &current_epoch - &retention
+        } else {
+            BigUint::from(1u64)
+        };
+
+        OptionalValue::Some((first_epoch, current_epoch).into())
+    }
+
+    // Returns the total weight and threshold of the signers
+    #[view(signersWeight)]
+    fn signers_weight(&self, epoch: BigUint) -> MultiValue2<BigUint, BigUint> {
+        let signers = self.get_stored_signers(&epoch);
+
+        let mut total_weight = BigUint::zero();
+
+        for signer in signers.signers.iter() {
+            total_weight += &signer.weight;
+        }
+
+        (total_weight, signers.threshold).into()
+    }
+
+    fn get_stored_signers(&self, epoch: &BigUint) -> WeightedSigners<Self::Api> {
+        let signers_mapper = self.signers_by_epoch(epoch);
+
+        require!(!signers_mapper.is_empty(), "Invalid epoch");
+
+        signers_mapper.get()
+    }
+
     #[view(timeSinceRotation)]
     fn time_since_rotation(&self) -> u64 {
         self.blockchain().get_block_timestamp() - self.last_rotation_timestamp().get()
@@ -196,6 +621,10 @@ pub trait AuthModule: events::Events {
     #[storage_mapper("last_rotation_timestamp")]
     fn last_rotation_timestamp(&self) -> SingleValueMapper<u64>;
 
+    #[view(scheduledRotation)]
+    #[storage_mapper("scheduled_rotation")]
+    fn scheduled_rotation(&self) -> SingleValueMapper<ScheduledRotation<Self::Api>>;
+
     #[view(signerHashByEpoch)]
     #[storage_mapper("signer_hash_by_epoch")]
     fn signer_hash_by_epoch(
@@ -203,6 +632,15 @@ pub trait AuthModule: events::Events {
         epoch: &BigUint,
     ) -> SingleValueMapper<ManagedByteArray<KECCAK256_RESULT_LEN>>;
 
+    // Signer epochs which can no longer be used to validate proofs, even if they are inside the retention window
+    #[view(revokedEpochs)]
+    #[storage_mapper("revoked_epochs")]
+    fn revoked_epochs(&self) -> UnorderedSetMapper<BigUint>;
+
+    #[view(signersByEpoch)]
+    #[storage_mapper("signers_by_epoch")]
+    fn signers_by_epoch(&self, epoch: &BigUint) -> SingleValueMapper<WeightedSigners<Self::Api>>;
+
     #[view(epochBySignerHash)]
     #[storage_mapper("epoch_by_signer_hash")]
     fn epoch_by_signer_hash(
@@ -222,6 +660,17 @@ pub trait AuthModule: events::Events {
     #[storage_mapper("domain_separator")]
     fn domain_separator(&self) -> SingleValueMapper<ManagedByteArray<KECCAK256_RESULT_LEN>>;
 
+    // Domain separator which is still accepted until the expiry timestamp, after the domain separator was rotated
+    #[view(previousDomainSeparator)]
+    #[storage_mapper("previous_domain_separator")]
+    fn previous_domain_separator(
+        &self,
+    ) -> SingleValueMapper<ManagedByteArray<KECCAK256_RESULT_LEN>>;
+
+    #[view(previousDomainSeparatorExpiry)]
+    #[storage_mapper("previous_domain_separator_expiry")]
+    fn previous_domain_separator_expiry(&self) -> SingleValueMapper<u64>;
+
     /// @dev The minimum delay required between rotations
     /// @return The minimum delay required between rotations
     #[view(minimumRotationDelay)]
@@ -1,6 +1,5 @@
 use core::convert::TryFrom;
 use multiversx_sc::api::ED25519_KEY_BYTE_LEN;
-use multiversx_sc::api::ED25519_SIGNATURE_BYTE_LEN;
 use multiversx_sc::api::KECCAK256_RESULT_LEN;
 
 multiversx_sc::imports!();
@@ -8,10 +7,31 @@ multiversx_sc::derive_imports!();
 
 pub const MULTIVERSX_SIGNED_MESSAGE_PREFIX: &[u8; 28] = b"\x19MultiversX Signed Message:\n";
 
+// Compressed secp256k1 public key
+pub const SECP256K1_KEY_BYTE_LEN: usize = 33;
+// Signature in the `r || s` format, optionally followed by the recovery id which is ignored
+pub const SECP256K1_SIGNATURE_BYTE_LEN: usize = 64;
+pub const SECP256K1_RECOVERABLE_SIGNATURE_BYTE_LEN: usize = 65;
+const DER_SEQUENCE_TAG: u8 = 0x30;
+const DER_INTEGER_TAG: u8 = 0x02;
+
+// Longest time proofs signed with the previous domain separator are accepted for after a rotation
+pub const MAX_DOMAIN_SEPARATOR_GRACE_PERIOD: u64 = 30 * 24 * 3600;
+
+// Compact proofs support signer sets of up to 256 signers
+pub const MAX_SIGNERS_BITMAP_LEN: usize = 32;
+
+// Endpoint that contracts supporting cross chain calls implement, called by approveAndExecute
+pub const EXECUTE_ENDPOINT: &[u8] = b"execute";
+pub const APPROVE_AND_EXECUTE_CALLBACK_GAS: u64 = 5_000_000;
+pub const KEEP_EXTRA_GAS: u64 = 10_000_000; // Extra gas to keep in contract before registering async promise
+
 #[derive(TypeAbi, TopEncode, NestedEncode)]
 pub enum CommandType {
     ApproveMessages,
     RotateSigners,
+    CancelRotateSigners,
+    RotateDomainSeparator,
 }
 
 #[derive(TypeAbi, TopDecode, TopEncode, NestedEncode, NestedDecode, ManagedVecItem)]
@@ -23,9 +43,76 @@ pub struct Message<M: ManagedTypeApi> {
     pub payload_hash: ManagedByteArray<M, KECCAK256_RESULT_LEN>,
 }
 
+#[derive(
+    TypeAbi,
+    TopDecode,
+    TopEncode,
+    NestedEncode,
+    NestedDecode,
+    ManagedVecItem,
+    PartialEq,
+    PartialOrd,
+    Clone,
+    Copy,
+)]
+pub enum SignerKeyType {
+    Ed25519,
+    Secp256k1,
+}
+
+impl SignerKeyType {
+    pub fn key_length(&self) -> usize {
+        match self {
+            SignerKeyType::Ed25519 => ED25519_KEY_BYTE_LEN,
+            SignerKeyType::Secp256k1 => SECP256K1_KEY_BYTE_LEN,
+        }
+    }
+}
+
+// The VM verifies secp256k1 signatures in the DER format, while signers provide them as `r || s`.
+// The signature needs to have at least 64 bytes, any recovery id after them is ignored.
+// Both integers are minimally encoded, with a zero byte prefix when their first bit is set so they are not negative
+pub fn encode_secp256k1_der_signature<M: ManagedTypeApi>(
+    signature: &ManagedBuffer<M>,
+) -> ManagedBuffer<M> {
+    let mut bytes = [0u8; SECP256K1_SIGNATURE_BYTE_LEN];
+
+    let _ = signature.load_slice(0, &mut bytes);
+
+    let (r, s) = bytes.split_at(SECP256K1_SIGNATURE_BYTE_LEN / 2);
+
+    let mut integers = ManagedBuffer::new();
+    append_der_integer(&mut integers, r);
+    append_der_integer(&mut integers, s);
+
+    let mut encoded = ManagedBuffer::new();
+    encoded.append_bytes(&[DER_SEQUENCE_TAG, integers.len() as u8]);
+    encoded.append(&integers);
+
+    encoded
+}
+
+fn append_der_integer<M: ManagedTypeApi>(encoded: &mut ManagedBuffer<M>, value: &[u8]) {
+    let first_non_zero = value
+        .iter()
+        .position(|byte| *byte != 0)
+        .unwrap_or(value.len() - 1);
+    let value = &value[first_non_zero..];
+    let is_padded = value[0] & 0x80 != 0;
+
+    encoded.append_bytes(&[DER_INTEGER_TAG, (value.len() + is_padded as usize) as u8]);
+    if is_padded {
+        encoded.append_bytes(&[0]);
+    }
+    encoded.append_bytes(value);
+}
+
+// The key type is part of the nested encoding of a signer, and hence of the signers hash of sets containing
+// secp256k1 signers, so the same key bytes can never be interpreted under a different scheme
 #[derive(TypeAbi, TopDecode, TopEncode, NestedEncode, NestedDecode, ManagedVecItem, PartialEq)]
 pub struct WeightedSigner<M: ManagedTypeApi> {
-    pub signer: ManagedByteArray<M, ED25519_KEY_BYTE_LEN>,
+    pub key_type: SignerKeyType,
+    pub signer: ManagedBuffer<M>,
     pub weight: BigUint<M>,
 }
 
@@ -36,13 +123,57 @@ pub struct WeightedSigners<M: ManagedTypeApi> {
     pub nonce: ManagedByteArray<M, KECCAK256_RESULT_LEN>,
 }
 
-#[derive(TypeAbi, TopDecode)]
+// Signed by the latest signers to move to a new domain separator. Proofs using the previous domain separator
+// are still accepted for `grace_period` seconds
+#[derive(TypeAbi, TopDecode, TopEncode, NestedEncode, NestedDecode)]
+pub struct DomainSeparatorRotation<M: ManagedTypeApi> {
+    pub new_domain_separator: ManagedByteArray<M, KECCAK256_RESULT_LEN>,
+    pub grace_period: u64,
+}
+
+// Operator forced signers rotation, which can be executed by anyone once the eta is reached
+#[derive(TypeAbi, TopDecode, TopEncode, NestedEncode, NestedDecode)]
+pub struct ScheduledRotation<M: ManagedTypeApi> {
+    pub new_signers: WeightedSigners<M>,
+    pub eta: u64,
+}
+
+#[derive(TypeAbi, TopDecode, TopEncode)]
 pub struct Proof<M: ManagedTypeApi> {
     pub signers: WeightedSigners<M>,
-    pub signatures: ManagedVec<M, Option<ManagedByteArray<M, ED25519_SIGNATURE_BYTE_LEN>>>,
+    pub signatures: ManagedVec<M, Option<ManagedBuffer<M>>>,
+}
+
+// References signers stored on chain by their epoch instead of containing them. The bitmap has one bit for
+// each signer, in order, with the least significant bit of the first byte representing the first signer.
+// Signatures are only provided for signers which have their bit set, in the same order as the signers
+#[derive(TypeAbi, TopDecode, TopEncode)]
+pub struct CompactProof<M: ManagedTypeApi> {
+    pub epoch: BigUint<M>,
+    pub signers_bitmap: ManagedBuffer<M>,
+    pub signatures: ManagedVec<M, ManagedBuffer<M>>,
+}
+
+// At most `limit` messages from a source chain can be approved in each window of `window` seconds
+#[derive(TypeAbi, TopDecode, TopEncode, NestedDecode, NestedEncode)]
+pub struct ApprovalRateLimit {
+    pub limit: u64,
+    pub window: u64,
+}
+
+#[derive(TypeAbi, TopDecode, TopEncode, NestedDecode, NestedEncode)]
+pub struct ApprovalWindow {
+    pub index: u64,
+    pub count: u64,
 }
 
-#[derive(TypeAbi, TopDecode, TopEncode, NestedEncode)]
+#[derive(TypeAbi, TopEncode, NestedEncode, Clone, Copy, PartialEq)]
+pub enum MessageRejectionReason {
+    SourceChainNotAllowed,
+    RateLimitExceeded,
+}
+
+#[derive(TypeAbi, TopDecode, TopEncode, NestedDecode, NestedEncode)]
 pub struct CrossChainId<M: ManagedTypeApi> {
     pub source_chain: ManagedBuffer<M>,
     pub message_id: ManagedBuffer<M>,
@@ -98,3 +229,21 @@ impl<M: ManagedTypeApi> codec::TopDecode for MessageState<M> {
         }
     }
 }
+
+// Typed version of the message state, using the standard encoding, returned by views
+#[derive(TypeAbi, TopEncode, NestedEncode, PartialEq)]
+pub enum MessageStatus<M: ManagedTypeApi> {
+    NonExistent,
+    Approved(ManagedByteArray<M, KECCAK256_RESULT_LEN>),
+    Executed,
+}
+
+impl<M: ManagedTypeApi> From<MessageState<M>> for MessageStatus<M> {
+    fn from(state: MessageState<M>) -> Self {
+        match state {
+            MessageState::NonExistent => MessageStatus::NonExistent,
+            MessageState::Approved(hash) => MessageStatus::Approved(hash),
+            MessageState::Executed => MessageStatus::Executed,
+        }
+    }
+}
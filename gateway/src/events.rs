@@ -2,7 +2,7 @@ multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
 use multiversx_sc::api::KECCAK256_RESULT_LEN;
-use crate::constants::WeightedSigners;
+use crate::constants::{ApprovalRateLimit, MessageRejectionReason, WeightedSigners};
 
 #[multiversx_sc::module]
 pub trait Events {
@@ -13,6 +13,7 @@ pub trait Events {
         #[indexed] destination_chain: ManagedBuffer,
         #[indexed] destination_contract_address: ManagedBuffer,
         #[indexed] payload_hash: ManagedByteArray<KECCAK256_RESULT_LEN>,
+        #[indexed] message_id: &ManagedByteArray<KECCAK256_RESULT_LEN>,
         payload: ManagedBuffer,
     );
 
@@ -26,6 +27,14 @@ pub trait Events {
         #[indexed] payload_hash: ManagedByteArray<KECCAK256_RESULT_LEN>,
     );
 
+    #[event("message_rejected_event")]
+    fn message_rejected_event(
+        &self,
+        #[indexed] source_chain: &ManagedBuffer,
+        #[indexed] message_id: &ManagedBuffer,
+        #[indexed] reason: MessageRejectionReason,
+    );
+
     #[event("message_executed_event")]
     fn message_executed_event(
         &self,
@@ -33,6 +42,15 @@ pub trait Events {
         #[indexed] message_id: &ManagedBuffer,
     );
 
+    #[event("message_execution_failed_event")]
+    fn message_execution_failed_event(
+        &self,
+        #[indexed] source_chain: &ManagedBuffer,
+        #[indexed] message_id: &ManagedBuffer,
+        #[indexed] err_code: u32,
+        err_msg: ManagedBuffer,
+    );
+
     #[event("signers_rotated_event")]
     fn signers_rotated_event(
         &self,
@@ -41,6 +59,77 @@ pub trait Events {
         signers: WeightedSigners<Self::Api>,
     );
 
+    #[event("signers_rotation_scheduled_event")]
+    fn signers_rotation_scheduled_event(
+        &self,
+        #[indexed] signers_hash: ManagedByteArray<KECCAK256_RESULT_LEN>,
+        #[indexed] eta: u64,
+    );
+
+    #[event("signers_rotation_cancelled_event")]
+    fn signers_rotation_cancelled_event(
+        &self,
+        #[indexed] signers_hash: ManagedByteArray<KECCAK256_RESULT_LEN>,
+    );
+
+    #[event("signers_revoked_event")]
+    fn signers_revoked_event(
+        &self,
+        #[indexed] epoch: BigUint, // This has nothing to do with the blockchain epoch
+        #[indexed] signers_hash: ManagedByteArray<KECCAK256_RESULT_LEN>,
+    );
+
+    #[event("signers_unrevoked_event")]
+    fn signers_unrevoked_event(
+        &self,
+        #[indexed] epoch: BigUint, // This has nothing to do with the blockchain epoch
+        #[indexed] signers_hash: ManagedByteArray<KECCAK256_RESULT_LEN>,
+    );
+
+    #[event("domain_separator_rotated_event")]
+    fn domain_separator_rotated_event(
+        &self,
+        #[indexed] new_domain_separator: &ManagedByteArray<KECCAK256_RESULT_LEN>,
+        #[indexed] previous_domain_separator: &ManagedByteArray<KECCAK256_RESULT_LEN>,
+        grace_period_end: u64,
+    );
+
     #[event("operatorship_transferred_event")]
     fn operatorship_transferred_event(&self, new_operator: ManagedAddress);
+
+    #[event("operatorship_proposed_event")]
+    fn operatorship_proposed_event(
+        &self,
+        #[indexed] current_operator: ManagedAddress,
+        #[indexed] pending_operator: ManagedAddress,
+    );
+
+    #[event("operatorship_proposal_cancelled_event")]
+    fn operatorship_proposal_cancelled_event(
+        &self,
+        #[indexed] current_operator: ManagedAddress,
+        #[indexed] pending_operator: ManagedAddress,
+    );
+
+    #[event("paused_event")]
+    fn paused_event(&self, #[indexed] account: ManagedAddress);
+
+    #[event("unpaused_event")]
+    fn unpaused_event(&self, #[indexed] account: ManagedAddress);
+
+    #[event("source_chain_allowlist_enabled_event")]
+    fn source_chain_allowlist_enabled_event(&self, #[indexed] enabled: bool);
+
+    #[event("source_chain_allowed_event")]
+    fn source_chain_allowed_event(&self, #[indexed] source_chain: &ManagedBuffer);
+
+    #[event("source_chain_disallowed_event")]
+    fn source_chain_disallowed_event(&self, #[indexed] source_chain: &ManagedBuffer);
+
+    #[event("approval_rate_limit_set_event")]
+    fn approval_rate_limit_set_event(
+        &self,
+        #[indexed] source_chain: &ManagedBuffer,
+        rate_limit: Option<ApprovalRateLimit>,
+    );
 }
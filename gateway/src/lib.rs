@@ -7,12 +7,20 @@ use crate::constants::*;
 multiversx_sc::imports!();
 
 mod auth;
-mod constants;
+pub mod constants;
 mod events;
 mod operator;
+mod pause;
+mod source_chains;
 
 #[multiversx_sc::contract]
-pub trait Gateway: auth::AuthModule + operator::OperatorModule + events::Events {
+pub trait Gateway:
+    auth::AuthModule
+    + operator::OperatorModule
+    + pause::PauseModule
+    + source_chains::SourceChainsModule
+    + events::Events
+{
     #[init]
     fn init(
         &self,
@@ -36,6 +44,9 @@ pub trait Gateway: auth::AuthModule + operator::OperatorModule + events::Events
         operator: ManagedAddress,
         signers: MultiValueEncoded<WeightedSigners<Self::Api>>,
     ) {
+        // A proposal made before the upgrade should not outlive it
+        self.pending_operator().clear();
+
         if !operator.is_zero() {
             self.transfer_operatorship_raw(operator);
         }
@@ -47,42 +58,266 @@ pub trait Gateway: auth::AuthModule + operator::OperatorModule + events::Events
 
     /// External Functions
 
+    // Returns the number of newly approved messages and the number of skipped messages
     #[endpoint(approveMessages)]
-    fn approve_messages(&self, messages: ManagedBuffer, proof: Proof<Self::Api>) {
+    fn approve_messages(
+        &self,
+        messages: ManagedBuffer,
+        proof: Proof<Self::Api>,
+        domain_separator: OptionalValue<ManagedByteArray<KECCAK256_RESULT_LEN>>,
+    ) -> MultiValue2<u32, u32> {
+        self.require_not_paused();
+
         let data_hash = self.get_data_hash(CommandType::ApproveMessages, &messages);
 
-        // Decode manually since it is more efficient to do it after we calculate the above hash
-        let messages: ManagedVec<Self::Api, Message<Self::Api>> =
-            ManagedVec::<Self::Api, Message<Self::Api>>::top_decode(messages)
-                .unwrap_or_else(|_| sc_panic!("Could not decode messages"));
+        let messages = self.decode_messages(messages);
 
-        require!(!messages.is_empty(), "Invalid messages");
+        let _ = self.validate_proof(data_hash, proof, domain_separator);
 
-        let _ = self.validate_proof(data_hash, proof);
+        self.approve_messages_raw(messages)
+    }
 
-        for message in messages.into_iter() {
-            self.approve_message(message);
-        }
+    #[endpoint(approveMessagesCompact)]
+    fn approve_messages_compact(
+        &self,
+        messages: ManagedBuffer,
+        proof: CompactProof<Self::Api>,
+        domain_separator: OptionalValue<ManagedByteArray<KECCAK256_RESULT_LEN>>,
+    ) -> MultiValue2<u32, u32> {
+        self.require_not_paused();
+
+        let data_hash = self.get_data_hash(CommandType::ApproveMessages, &messages);
+
+        let messages = self.decode_messages(messages);
+
+        let _ = self.validate_compact_proof(data_hash, proof, domain_separator);
+
+        self.approve_messages_raw(messages)
+    }
+
+    // Approves a single message and then calls the `execute` endpoint of its contract address in the same transaction.
+    // If the execution fails, the message stays approved and can be executed later
+    #[endpoint(approveAndExecute)]
+    fn approve_and_execute(
+        &self,
+        messages: ManagedBuffer,
+        proof: Proof<Self::Api>,
+        payload: ManagedBuffer,
+        execute_gas_limit: u64,
+        domain_separator: OptionalValue<ManagedByteArray<KECCAK256_RESULT_LEN>>,
+    ) {
+        self.require_not_paused();
+
+        let data_hash = self.get_data_hash(CommandType::ApproveMessages, &messages);
+
+        let messages = self.decode_messages(messages);
+
+        require!(messages.len() == 1, "Invalid messages");
+
+        let _ = self.validate_proof(data_hash, proof, domain_separator);
+
+        let message = messages.get(0);
+
+        require!(
+            self.crypto().keccak256(&payload) == message.payload_hash,
+            "Invalid payload"
+        );
+
+        let cross_chain_id = CrossChainId {
+            source_chain: message.source_chain.clone(),
+            message_id: message.message_id.clone(),
+        };
+        let source_address = message.source_address.clone();
+        let contract_address = message.contract_address.clone();
+        let payload_hash = message.payload_hash.clone();
+
+        self.approve_message(message, self.source_chain_allowlist_enabled().get());
+
+        // The message might have been rejected or already executed
+        require!(
+            self.is_message_approved_raw(
+                &cross_chain_id,
+                &source_address,
+                &contract_address,
+                &payload_hash,
+            ),
+            "Message not approved"
+        );
+
+        let gas_left = self.blockchain().get_gas_left();
+
+        // Overflow checks are disabled in release builds, so a huge gas limit must not wrap around
+        let required_gas = execute_gas_limit
+            .checked_add(APPROVE_AND_EXECUTE_CALLBACK_GAS + KEEP_EXTRA_GAS)
+            .unwrap_or_else(|| sc_panic!("Insufficient gas for execution"));
+
+        require!(gas_left > required_gas, "Insufficient gas for execution");
+
+        self.send()
+            .contract_call::<()>(contract_address, ManagedBuffer::from(EXECUTE_ENDPOINT))
+            .argument(&cross_chain_id.source_chain)
+            .argument(&cross_chain_id.message_id)
+            .argument(&source_address)
+            .argument(&payload)
+            .with_gas_limit(execute_gas_limit)
+            .async_call_promise()
+            .with_callback(self.callbacks().approve_and_execute_callback(
+                cross_chain_id.source_chain,
+                cross_chain_id.message_id,
+            ))
+            .with_extra_gas_for_callback(APPROVE_AND_EXECUTE_CALLBACK_GAS)
+            .register_promise();
     }
 
     #[endpoint(rotateSigners)]
-    fn rotate_signers(&self, new_signers: ManagedBuffer, proof: Proof<Self::Api>) {
+    fn rotate_signers(
+        &self,
+        new_signers: ManagedBuffer,
+        proof: Proof<Self::Api>,
+        domain_separator: OptionalValue<ManagedByteArray<KECCAK256_RESULT_LEN>>,
+    ) {
         let data_hash = self.get_data_hash(CommandType::RotateSigners, &new_signers);
 
-        // Decode manually since it is more efficient to do it after we calculate the above hash
-        let new_signers: WeightedSigners<Self::Api> =
-            WeightedSigners::<Self::Api>::top_decode(new_signers)
-                .unwrap_or_else(|_| sc_panic!("Could not decode new signers"));
+        let new_signers = self.decode_new_signers(new_signers);
 
-        let enforce_rotation_delay = self.blockchain().get_caller() != self.operator().get();
-        let is_latest_signers = self.validate_proof(data_hash, proof);
+        let is_latest_signers = self.validate_proof(data_hash, proof, domain_separator);
+
+        self.rotate_signers_with_proof(new_signers, is_latest_signers);
+    }
+
+    #[endpoint(rotateSignersCompact)]
+    fn rotate_signers_compact(
+        &self,
+        new_signers: ManagedBuffer,
+        proof: CompactProof<Self::Api>,
+        domain_separator: OptionalValue<ManagedByteArray<KECCAK256_RESULT_LEN>>,
+    ) {
+        let data_hash = self.get_data_hash(CommandType::RotateSigners, &new_signers);
+
+        let new_signers = self.decode_new_signers(new_signers);
+
+        let is_latest_signers = self.validate_compact_proof(data_hash, proof, domain_separator);
+
+        self.rotate_signers_with_proof(new_signers, is_latest_signers);
+    }
+
+    // Allows moving to a new Amplifier deployment without redeploying the Gateway
+    #[endpoint(rotateDomainSeparator)]
+    fn rotate_domain_separator(&self, rotation: ManagedBuffer, proof: Proof<Self::Api>) {
+        let data_hash = self.get_data_hash(CommandType::RotateDomainSeparator, &rotation);
 
+        let rotation = DomainSeparatorRotation::<Self::Api>::top_decode(rotation)
+            .unwrap_or_else(|_| sc_panic!("Could not decode domain separator rotation"));
+
+        let is_latest_signers = self.validate_proof(data_hash, proof, OptionalValue::None);
+
+        require!(is_latest_signers, "Not latest signers");
+
+        self.rotate_domain_separator_raw(rotation.new_domain_separator, rotation.grace_period);
+    }
+
+    // Can be used by the operator or owner (governance) to invalidate signers whose keys were compromised
+    #[endpoint(revokeSigners)]
+    fn revoke_signers(&self, epoch: BigUint) {
+        self.only_operator_or_owner();
+
+        self.revoke_signers_raw(epoch);
+    }
+
+    #[endpoint(revokeSignersByHash)]
+    fn revoke_signers_by_hash(&self, signers_hash: ManagedByteArray<KECCAK256_RESULT_LEN>) {
+        self.only_operator_or_owner();
+
+        let epoch_mapper = self.epoch_by_signer_hash(&signers_hash);
+
+        require!(!epoch_mapper.is_empty(), "Invalid signers hash");
+
+        self.revoke_signers_raw(epoch_mapper.get());
+    }
+
+    // Restores signers revoked by mistake, including the latest signers which only the owner can revoke
+    #[only_owner]
+    #[endpoint(unrevokeSigners)]
+    fn unrevoke_signers(&self, epoch: BigUint) {
+        self.unrevoke_signers_raw(epoch);
+    }
+
+    // Can be called by anyone after an upgrade to move messages executed before it to the compact representation.
+    // Not done in the upgrade itself since the number of executed messages is unbounded
+    #[endpoint(pruneExecutedMessages)]
+    fn prune_executed_messages(&self, cross_chain_ids: MultiValueEncoded<CrossChainId<Self::Api>>) {
+        for cross_chain_id in cross_chain_ids.into_iter() {
+            let messages_mapper = self.messages(&cross_chain_id);
+
+            if messages_mapper.get() != MessageState::Executed {
+                continue;
+            }
+
+            messages_mapper.clear();
+            self.executed_messages(&self.cross_chain_id_hash(&cross_chain_id))
+                .set(true);
+        }
+    }
+
+    // Alternative to the operator forcing a rotation immediately, which gives governance
+    // or the current signers time to cancel the rotation if the operator key was compromised
+    #[endpoint(scheduleRotateSigners)]
+    fn schedule_rotate_signers(&self, new_signers: ManagedBuffer, proof: Proof<Self::Api>) {
         require!(
-            !enforce_rotation_delay || is_latest_signers,
-            "Not latest signers"
+            self.blockchain().get_caller() == self.operator().get(),
+            "Invalid sender"
         );
 
-        self.rotate_signers_raw(new_signers, enforce_rotation_delay);
+        let data_hash = self.get_data_hash(CommandType::RotateSigners, &new_signers);
+
+        let new_signers = self.decode_new_signers(new_signers);
+
+        let _ = self.validate_proof(data_hash, proof, OptionalValue::None);
+
+        self.schedule_rotation_raw(new_signers);
+    }
+
+    #[endpoint(executeScheduledRotation)]
+    fn execute_scheduled_rotation(&self) {
+        self.execute_scheduled_rotation_raw();
+    }
+
+    // Can be used by the owner (governance) to cancel a scheduled rotation
+    #[endpoint(cancelScheduledRotation)]
+    fn cancel_scheduled_rotation(&self) {
+        require!(
+            self.blockchain().get_caller() == self.blockchain().get_owner_address(),
+            "Invalid sender"
+        );
+
+        self.cancel_scheduled_rotation_raw();
+    }
+
+    // Can be used by anyone with a proof of the latest signers to cancel a scheduled rotation. The proof is over
+    // both the new signers and the eta, so it can not be replayed to cancel a later rotation to the same signers
+    #[endpoint(cancelScheduledRotationWithProof)]
+    fn cancel_scheduled_rotation_with_proof(&self, proof: Proof<Self::Api>) {
+        let scheduled_rotation_mapper = self.scheduled_rotation();
+
+        require!(
+            !scheduled_rotation_mapper.is_empty(),
+            "No scheduled rotation"
+        );
+
+        let mut scheduled_rotation = ManagedBuffer::new();
+
+        scheduled_rotation_mapper
+            .get()
+            .top_encode(&mut scheduled_rotation)
+            .unwrap_or_else(|_| sc_panic!("Could not encode scheduled rotation"));
+
+        let data_hash = self.get_data_hash(CommandType::CancelRotateSigners, &scheduled_rotation);
+
+        let is_latest_signers = self.validate_proof(data_hash, proof, OptionalValue::None);
+
+        require!(is_latest_signers, "Not latest signers");
+
+        self.cancel_scheduled_rotation_raw();
     }
 
     /// Public Methods
@@ -93,14 +328,30 @@ pub trait Gateway: auth::AuthModule + operator::OperatorModule + events::Events
         destination_chain: ManagedBuffer,
         destination_contract_address: ManagedBuffer,
         payload: ManagedBuffer,
-    ) {
+    ) -> ManagedByteArray<KECCAK256_RESULT_LEN> {
+        self.require_not_paused();
+
+        let sender = self.blockchain().get_caller();
+        let payload_hash = self.crypto().keccak256(&payload);
+
+        let nonce = self.outbound_nonce().update(|nonce| {
+            let current_nonce = *nonce;
+            *nonce += 1;
+            current_nonce
+        });
+
+        let message_id = self.outbound_message_id(nonce, &sender, &payload_hash);
+
         self.contract_call_event(
-            self.blockchain().get_caller(),
+            sender,
             destination_chain,
             destination_contract_address,
-            self.crypto().keccak256(&payload),
+            payload_hash,
+            &message_id,
             payload,
         );
+
+        message_id
     }
 
     // Can only be called by the appropriate contract address
@@ -112,6 +363,8 @@ pub trait Gateway: auth::AuthModule + operator::OperatorModule + events::Events
         source_address: &ManagedBuffer,
         payload_hash: &ManagedByteArray<KECCAK256_RESULT_LEN>,
     ) -> bool {
+        self.require_not_paused();
+
         let cross_chain_id = CrossChainId {
             source_chain,
             message_id,
@@ -128,7 +381,9 @@ pub trait Gateway: auth::AuthModule + operator::OperatorModule + events::Events
         let valid = messages_mapper.get() == MessageState::Approved(message_hash);
 
         if valid {
-            messages_mapper.set(MessageState::Executed);
+            messages_mapper.clear();
+            self.executed_messages(&self.cross_chain_id_hash(&cross_chain_id))
+                .set(true);
 
             self.message_executed_event(&cross_chain_id.source_chain, &cross_chain_id.message_id);
         }
@@ -136,9 +391,80 @@ pub trait Gateway: auth::AuthModule + operator::OperatorModule + events::Events
         valid
     }
 
+    #[promises_callback]
+    fn approve_and_execute_callback(
+        &self,
+        source_chain: ManagedBuffer,
+        message_id: ManagedBuffer,
+        #[call_result] call_result: ManagedAsyncCallResult<MultiValueEncoded<ManagedBuffer>>,
+    ) {
+        // On success the message was already marked as executed by the contract through validateMessage
+        if let ManagedAsyncCallResult::Err(err) = call_result {
+            self.message_execution_failed_event(
+                &source_chain,
+                &message_id,
+                err.err_code,
+                err.err_msg,
+            );
+        }
+    }
+
     // Self Functions
 
-    fn approve_message(&self, message: Message<Self::Api>) {
+    // Decode manually since it is more efficient to do it after we calculate the data hash
+    fn decode_messages(&self, messages: ManagedBuffer) -> ManagedVec<Message<Self::Api>> {
+        let messages: ManagedVec<Self::Api, Message<Self::Api>> =
+            ManagedVec::<Self::Api, Message<Self::Api>>::top_decode(messages)
+                .unwrap_or_else(|_| sc_panic!("Could not decode messages"));
+
+        require!(!messages.is_empty(), "Invalid messages");
+
+        messages
+    }
+
+    // Decode manually since it is more efficient to do it after we calculate the data hash
+    fn decode_new_signers(&self, new_signers: ManagedBuffer) -> WeightedSigners<Self::Api> {
+        WeightedSigners::<Self::Api>::top_decode(new_signers)
+            .unwrap_or_else(|_| sc_panic!("Could not decode new signers"))
+    }
+
+    fn approve_messages_raw(
+        &self,
+        messages: ManagedVec<Message<Self::Api>>,
+    ) -> MultiValue2<u32, u32> {
+        let mut approved_count = 0u32;
+        let mut skipped_count = 0u32;
+
+        let allowlist_enabled = self.source_chain_allowlist_enabled().get();
+
+        for message in messages.into_iter() {
+            if self.approve_message(message, allowlist_enabled) {
+                approved_count += 1;
+            } else {
+                skipped_count += 1;
+            }
+        }
+
+        (approved_count, skipped_count).into()
+    }
+
+    fn rotate_signers_with_proof(
+        &self,
+        new_signers: WeightedSigners<Self::Api>,
+        is_latest_signers: bool,
+    ) {
+        let enforce_rotation_delay = self.blockchain().get_caller() != self.operator().get();
+
+        require!(
+            !enforce_rotation_delay || is_latest_signers,
+            "Not latest signers"
+        );
+
+        self.rotate_signers_raw(new_signers, enforce_rotation_delay);
+    }
+
+    // Returns false if the message was skipped, either because it already exists or because it was rejected
+    fn approve_message(&self, message: Message<Self::Api>, allowlist_enabled: bool) -> bool {
         let cross_chain_id = CrossChainId {
             source_chain: message.source_chain,
             message_id: message.message_id,
@@ -146,8 +472,24 @@ pub trait Gateway: auth::AuthModule + operator::OperatorModule + events::Events
 
         let messages_mapper = self.messages(&cross_chain_id);
 
-        if messages_mapper.get() != MessageState::NonExistent {
-            return;
+        // Storage is checked before hashing the message, since relayers often resubmit already approved messages.
+        // The stored message state doesn't need to be decoded either
+        if !messages_mapper.is_empty()
+            || self
+                .executed_messages(&self.cross_chain_id_hash(&cross_chain_id))
+                .get()
+        {
+            return false;
+        }
+
+        if let Err(reason) = self.record_approval(&cross_chain_id.source_chain, allowlist_enabled) {
+            self.message_rejected_event(
+                &cross_chain_id.source_chain,
+                &cross_chain_id.message_id,
+                reason,
+            );
+
+            return false;
         }
 
         let message_hash = self.message_hash(
@@ -166,6 +508,54 @@ pub trait Gateway: auth::AuthModule + operator::OperatorModule + events::Events
             message.contract_address,
             message.payload_hash,
         );
+
+        true
+    }
+
+    fn is_message_approved_raw(
+        &self,
+        cross_chain_id: &CrossChainId<Self::Api>,
+        source_address: &ManagedBuffer,
+        contract_address: &ManagedAddress,
+        payload_hash: &ManagedByteArray<KECCAK256_RESULT_LEN>,
+    ) -> bool {
+        let message_hash = self.message_hash(
+            cross_chain_id,
+            source_address,
+            contract_address,
+            payload_hash,
+        );
+
+        self.messages(cross_chain_id).get() == MessageState::Approved(message_hash)
+    }
+
+    // Executed messages are only stored as a marker under the hash of their cross chain id to save storage,
+    // while messages executed before this was introduced can still be found in the messages storage
+    fn message_state(&self, cross_chain_id: &CrossChainId<Self::Api>) -> MessageState<Self::Api> {
+        let state = self.messages(cross_chain_id).get();
+
+        if state == MessageState::NonExistent
+            && self
+                .executed_messages(&self.cross_chain_id_hash(cross_chain_id))
+                .get()
+        {
+            return MessageState::Executed;
+        }
+
+        state
+    }
+
+    fn cross_chain_id_hash(
+        &self,
+        cross_chain_id: &CrossChainId<Self::Api>,
+    ) -> ManagedByteArray<KECCAK256_RESULT_LEN> {
+        let mut encoded = ManagedBuffer::new();
+
+        cross_chain_id
+            .top_encode(&mut encoded)
+            .unwrap_or_else(|_| sc_panic!("Could not encode cross chain id"));
+
+        self.crypto().keccak256(encoded)
     }
 
     fn message_hash(
@@ -193,6 +583,27 @@ pub trait Gateway: auth::AuthModule + operator::OperatorModule + events::Events
         self.crypto().keccak256(encoded)
     }
 
+    fn outbound_message_id(
+        &self,
+        nonce: u64,
+        sender: &ManagedAddress,
+        payload_hash: &ManagedByteArray<KECCAK256_RESULT_LEN>,
+    ) -> ManagedByteArray<KECCAK256_RESULT_LEN> {
+        let mut encoded = ManagedBuffer::new();
+
+        nonce
+            .dep_encode(&mut encoded)
+            .unwrap_or_else(|_| sc_panic!("Could not encode nonce"));
+        sender
+            .dep_encode(&mut encoded)
+            .unwrap_or_else(|_| sc_panic!("Could not encode sender"));
+        payload_hash
+            .dep_encode(&mut encoded)
+            .unwrap_or_else(|_| sc_panic!("Could not encode payload hash"));
+
+        self.crypto().keccak256(encoded)
+    }
+
     fn get_data_hash(
         &self,
         command_type: CommandType,
@@ -222,14 +633,36 @@ pub trait Gateway: auth::AuthModule + operator::OperatorModule + events::Events
             message_id,
         };
 
-        let message_hash = self.message_hash(
+        self.is_message_approved_raw(
             &cross_chain_id,
             source_address,
             contract_address,
             payload_hash,
-        );
+        )
+    }
+
+    #[view(areMessagesApproved)]
+    fn are_messages_approved(
+        &self,
+        messages: MultiValueEncoded<Message<Self::Api>>,
+    ) -> MultiValueEncoded<bool> {
+        let mut result = MultiValueEncoded::new();
 
-        self.messages(&cross_chain_id).get() == MessageState::Approved(message_hash)
+        for message in messages.into_iter() {
+            let cross_chain_id = CrossChainId {
+                source_chain: message.source_chain,
+                message_id: message.message_id,
+            };
+
+            result.push(self.is_message_approved_raw(
+                &cross_chain_id,
+                &message.source_address,
+                &message.contract_address,
+                &message.payload_hash,
+            ));
+        }
+
+        result
     }
 
     #[view(isMessageExecuted)]
@@ -239,7 +672,26 @@ pub trait Gateway: auth::AuthModule + operator::OperatorModule + events::Events
             message_id,
         };
 
-        self.messages(&cross_chain_id).get() == MessageState::Executed
+        self.message_state(&cross_chain_id) == MessageState::Executed
+    }
+
+    // Nonce of the next outgoing message, used to derive unique outbound message ids
+    #[view(outboundNonce)]
+    #[storage_mapper("outbound_nonce")]
+    fn outbound_nonce(&self) -> SingleValueMapper<u64>;
+
+    #[view(messagesStatus)]
+    fn messages_status(
+        &self,
+        cross_chain_ids: MultiValueEncoded<CrossChainId<Self::Api>>,
+    ) -> MultiValueEncoded<MessageStatus<Self::Api>> {
+        let mut result = MultiValueEncoded::new();
+
+        for cross_chain_id in cross_chain_ids.into_iter() {
+            result.push(MessageStatus::from(self.message_state(&cross_chain_id)));
+        }
+
+        result
     }
 
     #[view(messages)]
@@ -248,4 +700,11 @@ pub trait Gateway: auth::AuthModule + operator::OperatorModule + events::Events
         &self,
         cross_chain_id: &CrossChainId<Self::Api>,
     ) -> SingleValueMapper<MessageState<Self::Api>>;
+
+    #[view(executedMessages)]
+    #[storage_mapper("executed_messages")]
+    fn executed_messages(
+        &self,
+        cross_chain_id_hash: &ManagedByteArray<KECCAK256_RESULT_LEN>,
+    ) -> SingleValueMapper<bool>;
 }
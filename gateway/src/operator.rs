@@ -4,15 +4,56 @@ multiversx_sc::imports!();
 
 #[multiversx_sc::module]
 pub trait OperatorModule: events::Events {
+    // Direct transfer reserved to the owner (governance), the operator needs to go through a proposal
+    #[only_owner]
     #[endpoint(transferOperatorship)]
     fn transfer_operatorship(&self, new_operator: ManagedAddress) {
+        require!(!new_operator.is_zero(), "Invalid operator");
+
+        self.pending_operator().clear();
+
+        self.transfer_operatorship_raw(new_operator);
+    }
+
+    #[endpoint(proposeOperatorship)]
+    fn propose_operatorship(&self, new_operator: ManagedAddress) {
         self.only_operator_or_owner();
 
         require!(!new_operator.is_zero(), "Invalid operator");
 
+        self.pending_operator().set(&new_operator);
+
+        self.operatorship_proposed_event(self.operator().get(), new_operator);
+    }
+
+    #[endpoint(acceptOperatorship)]
+    fn accept_operatorship(&self) {
+        let pending_operator_mapper = self.pending_operator();
+
+        require!(
+            !pending_operator_mapper.is_empty()
+                && self.blockchain().get_caller() == pending_operator_mapper.get(),
+            "Invalid sender"
+        );
+
+        let new_operator = pending_operator_mapper.take();
+
         self.transfer_operatorship_raw(new_operator);
     }
 
+    #[endpoint(cancelOperatorshipProposal)]
+    fn cancel_operatorship_proposal(&self) {
+        self.only_operator_or_owner();
+
+        let pending_operator_mapper = self.pending_operator();
+
+        require!(!pending_operator_mapper.is_empty(), "No pending operator");
+
+        let pending_operator = pending_operator_mapper.take();
+
+        self.operatorship_proposal_cancelled_event(self.operator().get(), pending_operator);
+    }
+
     fn only_operator_or_owner(&self) {
         let caller = self.blockchain().get_caller();
 
@@ -31,4 +72,8 @@ pub trait OperatorModule: events::Events {
     #[view(operator)]
     #[storage_mapper("operator")]
     fn operator(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(pendingOperator)]
+    #[storage_mapper("pending_operator")]
+    fn pending_operator(&self) -> SingleValueMapper<ManagedAddress>;
 }
@@ -0,0 +1,38 @@
+use crate::{events, operator};
+
+multiversx_sc::imports!();
+
+// Allows the operator or owner to halt message approvals, validations and outgoing calls during an incident.
+// Signer rotations are still allowed while paused, so the signer set can be recovered
+#[multiversx_sc::module]
+pub trait PauseModule: operator::OperatorModule + events::Events {
+    #[endpoint]
+    fn pause(&self) {
+        self.only_operator_or_owner();
+
+        require!(!self.paused().get(), "Already paused");
+
+        self.paused().set(true);
+
+        self.paused_event(self.blockchain().get_caller());
+    }
+
+    #[endpoint]
+    fn unpause(&self) {
+        self.only_operator_or_owner();
+
+        require!(self.paused().get(), "Not paused");
+
+        self.paused().clear();
+
+        self.unpaused_event(self.blockchain().get_caller());
+    }
+
+    fn require_not_paused(&self) {
+        require!(!self.paused().get(), "Gateway is paused");
+    }
+
+    #[view(isPaused)]
+    #[storage_mapper("paused")]
+    fn paused(&self) -> SingleValueMapper<bool>;
+}
@@ -0,0 +1,135 @@
+use crate::constants::{ApprovalRateLimit, ApprovalWindow, MessageRejectionReason};
+use crate::{events, operator};
+
+multiversx_sc::imports!();
+
+// Defence in depth on top of the signers: the operator or owner can restrict the source chains messages
+// are approved from and limit the number of approvals per source chain in a time window
+#[multiversx_sc::module]
+pub trait SourceChainsModule: operator::OperatorModule + events::Events {
+    #[endpoint(setSourceChainAllowlistEnabled)]
+    fn set_source_chain_allowlist_enabled(&self, enabled: bool) {
+        self.only_operator_or_owner();
+
+        self.source_chain_allowlist_enabled().set(enabled);
+
+        self.source_chain_allowlist_enabled_event(enabled);
+    }
+
+    #[endpoint(allowSourceChains)]
+    fn allow_source_chains(&self, source_chains: MultiValueEncoded<ManagedBuffer>) {
+        self.only_operator_or_owner();
+
+        for source_chain in source_chains.into_iter() {
+            require!(!source_chain.is_empty(), "Invalid source chain");
+
+            if self.allowed_source_chains().insert(source_chain.clone()) {
+                self.source_chain_allowed_event(&source_chain);
+            }
+        }
+    }
+
+    #[endpoint(disallowSourceChains)]
+    fn disallow_source_chains(&self, source_chains: MultiValueEncoded<ManagedBuffer>) {
+        self.only_operator_or_owner();
+
+        for source_chain in source_chains.into_iter() {
+            if self.allowed_source_chains().swap_remove(&source_chain) {
+                self.source_chain_disallowed_event(&source_chain);
+            }
+        }
+    }
+
+    // A limit of 0 removes the rate limit of the source chain
+    #[endpoint(setApprovalRateLimit)]
+    fn set_approval_rate_limit(&self, source_chain: ManagedBuffer, limit: u64, window: u64) {
+        self.only_operator_or_owner();
+
+        require!(!source_chain.is_empty(), "Invalid source chain");
+
+        // The current window is reset, since its size might have changed
+        self.approval_window(&source_chain).clear();
+
+        if limit == 0 {
+            self.approval_rate_limit(&source_chain).clear();
+
+            self.approval_rate_limit_set_event(&source_chain, None);
+
+            return;
+        }
+
+        require!(window > 0, "Invalid rate limit window");
+
+        let rate_limit = ApprovalRateLimit { limit, window };
+
+        self.approval_rate_limit(&source_chain).set(&rate_limit);
+
+        self.approval_rate_limit_set_event(&source_chain, Some(rate_limit));
+    }
+
+    // Checks that a message from the source chain can be approved, and if so counts it towards the rate limit.
+    // Whether the allowlist is enabled is read once per batch by the caller
+    fn record_approval(
+        &self,
+        source_chain: &ManagedBuffer,
+        allowlist_enabled: bool,
+    ) -> Result<(), MessageRejectionReason> {
+        if allowlist_enabled && !self.allowed_source_chains().contains(source_chain) {
+            return Err(MessageRejectionReason::SourceChainNotAllowed);
+        }
+
+        let rate_limit_mapper = self.approval_rate_limit(source_chain);
+
+        if rate_limit_mapper.is_empty() {
+            return Ok(());
+        }
+
+        let rate_limit = rate_limit_mapper.get();
+        let window_index = self.blockchain().get_block_timestamp() / rate_limit.window;
+
+        let window_mapper = self.approval_window(source_chain);
+
+        let mut window = if window_mapper.is_empty() {
+            ApprovalWindow {
+                index: window_index,
+                count: 0,
+            }
+        } else {
+            window_mapper.get()
+        };
+
+        if window.index != window_index {
+            window.index = window_index;
+            window.count = 0;
+        }
+
+        if window.count >= rate_limit.limit {
+            return Err(MessageRejectionReason::RateLimitExceeded);
+        }
+
+        window.count += 1;
+
+        window_mapper.set(window);
+
+        Ok(())
+    }
+
+    #[view(isSourceChainAllowlistEnabled)]
+    #[storage_mapper("source_chain_allowlist_enabled")]
+    fn source_chain_allowlist_enabled(&self) -> SingleValueMapper<bool>;
+
+    #[view(allowedSourceChains)]
+    #[storage_mapper("allowed_source_chains")]
+    fn allowed_source_chains(&self) -> UnorderedSetMapper<ManagedBuffer>;
+
+    #[view(approvalRateLimit)]
+    #[storage_mapper("approval_rate_limit")]
+    fn approval_rate_limit(
+        &self,
+        source_chain: &ManagedBuffer,
+    ) -> SingleValueMapper<ApprovalRateLimit>;
+
+    #[view(approvalWindow)]
+    #[storage_mapper("approval_window")]
+    fn approval_window(&self, source_chain: &ManagedBuffer) -> SingleValueMapper<ApprovalWindow>;
+}
@@ -0,0 +1,50 @@
+use gateway::constants::encode_secp256k1_der_signature;
+use k256::ecdsa::signature::hazmat::PrehashSigner;
+use k256::ecdsa::{Signature, SigningKey};
+use multiversx_sc::types::ManagedBuffer;
+use multiversx_sc_scenario::api::StaticApi;
+
+fn encode(signature: &[u8]) -> Vec<u8> {
+    encode_secp256k1_der_signature(&ManagedBuffer::<StaticApi>::from(signature)).to_vec()
+}
+
+#[test]
+fn encode_der_signature_known_vector() {
+    // r has its first bit set, so it is prefixed with a zero byte, s starts with zero bytes which are dropped
+    let mut signature = [0u8; 64];
+    signature[..32].copy_from_slice(&[0x80; 32]);
+    signature[34] = 0x7f;
+    signature[35..].copy_from_slice(&[0x11; 29]);
+
+    let mut expected = vec![0x30, 0x43, 0x02, 0x21, 0x00];
+    expected.extend_from_slice(&[0x80; 32]);
+    expected.extend_from_slice(&[0x02, 0x1e, 0x7f]);
+    expected.extend_from_slice(&[0x11; 29]);
+
+    assert_eq!(encode(&signature), expected);
+}
+
+#[test]
+fn encode_der_signature_ignores_recovery_id() {
+    let mut signature = [0x01u8; 65];
+    signature[64] = 0x1b;
+
+    assert_eq!(encode(&signature), encode(&signature[..64]));
+}
+
+#[test]
+fn encode_der_signature_matches_k256() {
+    let signing_key = SigningKey::from_slice(&[0x42; 32]).unwrap();
+
+    // Enough message hashes to get values of r with and without their first bit set
+    for index in 0..64u8 {
+        let message_hash = [index; 32];
+        let signature: Signature = signing_key.sign_prehash(&message_hash).unwrap();
+
+        assert_eq!(
+            encode(&signature.to_bytes()),
+            signature.to_der().as_bytes(),
+            "message hash {index}"
+        );
+    }
+}
@@ -0,0 +1,981 @@
+use gateway::constants::{
+    CommandType, DomainSeparatorRotation, Message, Proof, MAX_DOMAIN_SEPARATOR_GRACE_PERIOD,
+};
+use gateway_proof::{get_data_hash, keccak256, Ed25519Signer, Hash, SignerSet};
+use multiversx_sc::codec::top_encode_to_vec_u8_or_panic;
+use multiversx_sc::types::ManagedArgBuffer;
+use multiversx_sc_scenario::imports::*;
+
+const OWNER: TestAddress = TestAddress::new("owner");
+const OPERATOR: TestAddress = TestAddress::new("operator");
+const RELAYER: TestAddress = TestAddress::new("relayer");
+const CONTRACT: TestAddress = TestAddress::new("contract");
+const GATEWAY_ADDRESS: TestSCAddress = TestSCAddress::new("gateway");
+const CODE_PATH: MxscPath = MxscPath::new("output/gateway.mxsc.json");
+
+const DOMAIN_SEPARATOR: Hash = [0x11; 32];
+const PREVIOUS_SIGNERS_RETENTION: u64 = 1;
+const MINIMUM_ROTATION_DELAY: u64 = 3600;
+
+const SOURCE_CHAIN: &str = "ethereum";
+const SOURCE_ADDRESS: &str = "0x4976da71bF84D750b5451B053051158EC0A4E876";
+const PAYLOAD_HASH: Hash = [0x44; 32];
+
+// Signer sets are made of deterministic ed25519 keys, with the secret key being the seed repeated
+fn signer_set(seeds: &[u8], nonce: u8) -> SignerSet {
+    seeds
+        .iter()
+        .fold(SignerSet::new(2, [nonce; 32]), |signer_set, seed| {
+            signer_set
+                .with_signer(Ed25519Signer::from_secret_key(&[*seed; 32]).unwrap(), 1)
+                .unwrap()
+        })
+}
+
+fn default_signer_set() -> SignerSet {
+    signer_set(&[1, 2, 3], 0)
+}
+
+fn message(message_id: &str) -> Message<StaticApi> {
+    Message {
+        source_chain: ManagedBuffer::from(SOURCE_CHAIN),
+        message_id: ManagedBuffer::from(message_id),
+        source_address: ManagedBuffer::from(SOURCE_ADDRESS),
+        contract_address: ManagedAddress::from(&CONTRACT.eval_to_array()),
+        payload_hash: ManagedByteArray::from(&PAYLOAD_HASH),
+    }
+}
+
+fn messages(message_ids: &[&str]) -> ManagedVec<StaticApi, Message<StaticApi>> {
+    let mut messages = ManagedVec::new();
+
+    for message_id in message_ids {
+        messages.push(message(message_id));
+    }
+
+    messages
+}
+
+fn arg_buffer(args: Vec<Vec<u8>>) -> ManagedArgBuffer<StaticApi> {
+    let mut arg_buffer = ManagedArgBuffer::new();
+
+    for arg in args {
+        arg_buffer.push_arg_raw(ManagedBuffer::from(arg));
+    }
+
+    arg_buffer
+}
+
+fn approve_messages_args(
+    signer_set: &SignerSet,
+    message_ids: &[&str],
+) -> ManagedArgBuffer<StaticApi> {
+    arg_buffer(
+        signer_set
+            .approve_messages_args(&DOMAIN_SEPARATOR, &messages(message_ids))
+            .unwrap(),
+    )
+}
+
+fn rotate_signers_args(
+    signer_set: &SignerSet,
+    new_signer_set: &SignerSet,
+) -> ManagedArgBuffer<StaticApi> {
+    arg_buffer(
+        signer_set
+            .rotate_signers_args::<StaticApi>(&DOMAIN_SEPARATOR, new_signer_set)
+            .unwrap(),
+    )
+}
+
+fn world() -> ScenarioWorld {
+    let mut blockchain = ScenarioWorld::new();
+
+    blockchain.register_contract(CODE_PATH, gateway::ContractBuilder);
+
+    blockchain
+}
+
+fn deploy() -> ScenarioWorld {
+    let mut world = world();
+
+    world.account(OWNER).nonce(1);
+    world.account(OPERATOR).nonce(1);
+    world.account(RELAYER).nonce(1);
+    world.account(CONTRACT).nonce(1);
+
+    world
+        .tx()
+        .from(OWNER)
+        .raw_deploy()
+        .code(CODE_PATH)
+        .new_address(GATEWAY_ADDRESS)
+        .argument(&BigUint::<StaticApi>::from(PREVIOUS_SIGNERS_RETENTION))
+        .argument(&ManagedByteArray::<StaticApi, 32>::from(&DOMAIN_SEPARATOR))
+        .argument(&MINIMUM_ROTATION_DELAY)
+        .argument(&OPERATOR)
+        .argument(&default_signer_set().weighted_signers::<StaticApi>())
+        .run();
+
+    world
+}
+
+// State written by the Gateway before secp256k1 signers were supported, with the default signer set
+// rotated in as epoch 1 and its hash computed using the legacy encoding of ed25519 signers
+fn deploy_baseline() -> ScenarioWorld {
+    let mut world = world();
+
+    world.account(OWNER).nonce(1);
+    world.account(OPERATOR).nonce(1);
+    world.account(RELAYER).nonce(1);
+    world.account(CONTRACT).nonce(1);
+
+    let mut public_keys: Vec<Hash> = [1u8, 2, 3]
+        .iter()
+        .map(|seed| {
+            Ed25519Signer::from_secret_key(&[*seed; 32])
+                .unwrap()
+                .public_key()
+        })
+        .collect();
+    public_keys.sort();
+
+    let mut encoded_signers = 3u32.to_be_bytes().to_vec();
+    for public_key in public_keys.iter() {
+        encoded_signers.extend_from_slice(public_key);
+        encoded_signers.extend_from_slice(&[0, 0, 0, 1, 1]);
+    }
+    encoded_signers.extend_from_slice(&[0, 0, 0, 1, 2]);
+    encoded_signers.extend_from_slice(&[0; 32]);
+
+    let signers_hash = keccak256(&encoded_signers);
+    assert_eq!(signers_hash, default_signer_set().signers_hash());
+
+    let storage = [
+        (b"epoch".to_vec(), vec![1]),
+        (
+            [&b"signer_hash_by_epoch"[..], &[0, 0, 0, 1, 1]].concat(),
+            signers_hash.to_vec(),
+        ),
+        (
+            [&b"epoch_by_signer_hash"[..], &signers_hash].concat(),
+            vec![1],
+        ),
+        (b"previous_signers_retention".to_vec(), vec![1]),
+        (b"domain_separator".to_vec(), DOMAIN_SEPARATOR.to_vec()),
+        (
+            b"minimum_rotation_delay".to_vec(),
+            top_encode_to_vec_u8_or_panic(&MINIMUM_ROTATION_DELAY),
+        ),
+        (b"operator".to_vec(), OPERATOR.eval_to_array().to_vec()),
+    ];
+
+    let mut account = Account::new().owner(OWNER);
+    account.code = Some(world.code_expression(&CODE_PATH.eval_to_expr()));
+    for (key, value) in storage {
+        account.storage.insert(key.into(), value.as_slice().into());
+    }
+
+    world.set_state_step(SetStateStep::new().put_account(GATEWAY_ADDRESS, account));
+
+    world
+}
+
+fn approve_messages(
+    world: &mut ScenarioWorld,
+    signer_set: &SignerSet,
+    message_ids: &[&str],
+) -> (u32, u32) {
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("approveMessages")
+        .arguments_raw(approve_messages_args(signer_set, message_ids))
+        .original_result::<MultiValue2<u32, u32>>()
+        .returns(ReturnsResult)
+        .run()
+        .into_tuple()
+}
+
+fn rotate_signers(
+    world: &mut ScenarioWorld,
+    from: TestAddress,
+    signer_set: &SignerSet,
+    new_signer_set: &SignerSet,
+) {
+    world
+        .tx()
+        .from(from)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("rotateSigners")
+        .arguments_raw(rotate_signers_args(signer_set, new_signer_set))
+        .run();
+}
+
+fn rotate_signers_error(
+    world: &mut ScenarioWorld,
+    from: TestAddress,
+    signer_set: &SignerSet,
+    new_signer_set: &SignerSet,
+    message: &str,
+) {
+    world
+        .tx()
+        .from(from)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("rotateSigners")
+        .arguments_raw(rotate_signers_args(signer_set, new_signer_set))
+        .returns(ExpectError(4, message))
+        .run();
+}
+
+fn epoch_by_signer_hash(world: &mut ScenarioWorld, signer_set: &SignerSet) -> u64 {
+    world
+        .query()
+        .to(GATEWAY_ADDRESS)
+        .raw_call("epochBySignerHash")
+        .argument(&ManagedByteArray::<StaticApi, 32>::from(
+            &signer_set.signers_hash(),
+        ))
+        .original_result::<u64>()
+        .returns(ReturnsResult)
+        .run()
+}
+
+fn valid_epochs(world: &mut ScenarioWorld) -> Option<(u64, u64)> {
+    let valid_epochs = world
+        .query()
+        .to(GATEWAY_ADDRESS)
+        .raw_call("validEpochs")
+        .original_result::<OptionalValue<MultiValue2<u64, u64>>>()
+        .returns(ReturnsResult)
+        .run();
+
+    valid_epochs.into_option().map(|epochs| epochs.into_tuple())
+}
+
+fn is_message_approved(world: &mut ScenarioWorld, message_id: &str) -> bool {
+    world
+        .query()
+        .to(GATEWAY_ADDRESS)
+        .raw_call("isMessageApproved")
+        .argument(&SOURCE_CHAIN)
+        .argument(&message_id)
+        .argument(&SOURCE_ADDRESS)
+        .argument(&CONTRACT)
+        .argument(&ManagedByteArray::<StaticApi, 32>::from(&PAYLOAD_HASH))
+        .original_result::<bool>()
+        .returns(ReturnsResult)
+        .run()
+}
+
+fn is_message_executed(world: &mut ScenarioWorld, message_id: &str) -> bool {
+    world
+        .query()
+        .to(GATEWAY_ADDRESS)
+        .raw_call("isMessageExecuted")
+        .argument(&SOURCE_CHAIN)
+        .argument(&message_id)
+        .original_result::<bool>()
+        .returns(ReturnsResult)
+        .run()
+}
+
+fn validate_message(world: &mut ScenarioWorld, message_id: &str) -> bool {
+    world
+        .tx()
+        .from(CONTRACT)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("validateMessage")
+        .argument(&SOURCE_CHAIN)
+        .argument(&message_id)
+        .argument(&SOURCE_ADDRESS)
+        .argument(&ManagedByteArray::<StaticApi, 32>::from(&PAYLOAD_HASH))
+        .original_result::<bool>()
+        .returns(ReturnsResult)
+        .run()
+}
+
+#[test]
+fn approve_messages_test() {
+    let mut world = deploy();
+
+    let (approved, skipped) = approve_messages(
+        &mut world,
+        &default_signer_set(),
+        &["messageId", "otherMessageId"],
+    );
+
+    assert_eq!((approved, skipped), (2, 0));
+    assert!(is_message_approved(&mut world, "messageId"));
+    assert!(is_message_approved(&mut world, "otherMessageId"));
+
+    // Already approved messages are skipped
+    let (approved, skipped) = approve_messages(
+        &mut world,
+        &default_signer_set(),
+        &["messageId", "thirdMessageId"],
+    );
+
+    assert_eq!((approved, skipped), (1, 1));
+    assert!(is_message_approved(&mut world, "thirdMessageId"));
+}
+
+#[test]
+fn approve_messages_unknown_signers() {
+    let mut world = deploy();
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("approveMessages")
+        .arguments_raw(approve_messages_args(
+            &signer_set(&[4, 5, 6], 0),
+            &["messageId"],
+        ))
+        .returns(ExpectError(4, "Invalid signers"))
+        .run();
+
+    assert!(!is_message_approved(&mut world, "messageId"));
+}
+
+#[test]
+fn approve_messages_invalid_signature() {
+    let mut world = deploy();
+
+    // Signed for another domain separator
+    let args = default_signer_set()
+        .approve_messages_args(&[0x22; 32], &messages(&["messageId"]))
+        .unwrap();
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("approveMessages")
+        .arguments_raw(arg_buffer(args))
+        .returns(ExpectError(10, "invalid signature"))
+        .run();
+
+    assert!(!is_message_approved(&mut world, "messageId"));
+}
+
+#[test]
+fn approve_messages_low_signatures_weight() {
+    let mut world = deploy();
+
+    let mut args = default_signer_set()
+        .approve_messages_args(&DOMAIN_SEPARATOR, &messages(&["messageId"]))
+        .unwrap();
+
+    // Only keep the first signature, while the threshold requires two of them
+    let proof = Proof::<StaticApi>::top_decode(args[1].as_slice()).unwrap();
+
+    let mut signatures = ManagedVec::new();
+    signatures.push(proof.signatures.get(0));
+    signatures.push(None);
+    signatures.push(None);
+
+    args[1] = top_encode_to_vec_u8_or_panic(&Proof {
+        signers: proof.signers,
+        signatures,
+    });
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("approveMessages")
+        .arguments_raw(arg_buffer(args))
+        .returns(ExpectError(4, "Low signatures weight"))
+        .run();
+
+    assert!(!is_message_approved(&mut world, "messageId"));
+}
+
+#[test]
+fn rotate_signers_delay() {
+    let mut world = deploy();
+
+    let new_signer_set = signer_set(&[4, 5, 6], 1);
+
+    rotate_signers_error(
+        &mut world,
+        RELAYER,
+        &default_signer_set(),
+        &new_signer_set,
+        "Insufficient rotation delay",
+    );
+
+    world
+        .current_block()
+        .block_timestamp(MINIMUM_ROTATION_DELAY);
+
+    rotate_signers(&mut world, RELAYER, &default_signer_set(), &new_signer_set);
+
+    assert_eq!(epoch_by_signer_hash(&mut world, &new_signer_set), 2);
+}
+
+#[test]
+fn rotate_signers_operator_bypass() {
+    let mut world = deploy();
+
+    let new_signer_set = signer_set(&[4, 5, 6], 1);
+    let other_signer_set = signer_set(&[7, 8, 9], 2);
+
+    // The operator is not subject to the rotation delay
+    rotate_signers(&mut world, OPERATOR, &default_signer_set(), &new_signer_set);
+
+    assert_eq!(epoch_by_signer_hash(&mut world, &new_signer_set), 2);
+
+    world
+        .current_block()
+        .block_timestamp(MINIMUM_ROTATION_DELAY);
+
+    // Only the operator can rotate with signers which are not the latest ones
+    rotate_signers_error(
+        &mut world,
+        RELAYER,
+        &default_signer_set(),
+        &other_signer_set,
+        "Not latest signers",
+    );
+
+    rotate_signers(
+        &mut world,
+        OPERATOR,
+        &default_signer_set(),
+        &other_signer_set,
+    );
+
+    assert_eq!(epoch_by_signer_hash(&mut world, &other_signer_set), 3);
+}
+
+#[test]
+fn rotate_signers_retention() {
+    let mut world = deploy();
+
+    let new_signer_set = signer_set(&[4, 5, 6], 1);
+    let other_signer_set = signer_set(&[7, 8, 9], 2);
+
+    rotate_signers(&mut world, OPERATOR, &default_signer_set(), &new_signer_set);
+
+    // Previous signers are still valid while retained
+    assert_eq!(
+        approve_messages(&mut world, &default_signer_set(), &["messageId"]),
+        (1, 0)
+    );
+
+    rotate_signers(&mut world, OPERATOR, &new_signer_set, &other_signer_set);
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("approveMessages")
+        .arguments_raw(approve_messages_args(
+            &default_signer_set(),
+            &["otherMessageId"],
+        ))
+        .returns(ExpectError(4, "Invalid signers"))
+        .run();
+
+    assert_eq!(
+        approve_messages(&mut world, &new_signer_set, &["otherMessageId"]),
+        (1, 0)
+    );
+}
+
+#[test]
+fn rotate_signers_duplicate_signers() {
+    let mut world = deploy();
+
+    let new_signer_set = signer_set(&[4, 5, 6], 1);
+
+    rotate_signers(&mut world, OPERATOR, &default_signer_set(), &new_signer_set);
+
+    rotate_signers_error(
+        &mut world,
+        OPERATOR,
+        &new_signer_set,
+        &default_signer_set(),
+        "Duplicate signers",
+    );
+
+    assert_eq!(epoch_by_signer_hash(&mut world, &default_signer_set()), 1);
+}
+
+#[test]
+fn revoke_latest_signers() {
+    let mut world = deploy();
+
+    let new_signer_set = signer_set(&[4, 5, 6], 1);
+
+    rotate_signers(&mut world, OPERATOR, &default_signer_set(), &new_signer_set);
+
+    // The operator can revoke previous signers, but not the latest ones
+    world
+        .tx()
+        .from(OPERATOR)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("revokeSigners")
+        .argument(&1u64)
+        .run();
+
+    world
+        .tx()
+        .from(OPERATOR)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("revokeSigners")
+        .argument(&2u64)
+        .returns(ExpectError(4, "Can not revoke latest signers"))
+        .run();
+
+    assert_eq!(
+        approve_messages(&mut world, &new_signer_set, &["messageId"]),
+        (1, 0)
+    );
+
+    // The owner can revoke the latest signers, and restore them
+    world
+        .tx()
+        .from(OWNER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("revokeSigners")
+        .argument(&2u64)
+        .run();
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("approveMessages")
+        .arguments_raw(approve_messages_args(&new_signer_set, &["otherMessageId"]))
+        .returns(ExpectError(4, "Revoked signers"))
+        .run();
+
+    world
+        .tx()
+        .from(OPERATOR)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("unrevokeSigners")
+        .argument(&2u64)
+        .returns(ExpectError(4, "Endpoint can only be called by owner"))
+        .run();
+
+    world
+        .tx()
+        .from(OWNER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("unrevokeSigners")
+        .argument(&2u64)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("unrevokeSigners")
+        .argument(&2u64)
+        .returns(ExpectError(4, "Signers not revoked"))
+        .run();
+
+    assert_eq!(
+        approve_messages(&mut world, &new_signer_set, &["otherMessageId"]),
+        (1, 0)
+    );
+}
+
+#[test]
+fn approve_and_execute_gas_limit_overflow() {
+    let mut world = deploy();
+
+    let payload = b"payload";
+
+    let mut message = message("messageId");
+    message.payload_hash = ManagedByteArray::from(&keccak256(payload));
+
+    let mut args = default_signer_set()
+        .approve_messages_args(&DOMAIN_SEPARATOR, &ManagedVec::from_single_item(message))
+        .unwrap();
+    args.push(payload.to_vec());
+    args.push(top_encode_to_vec_u8_or_panic(&u64::MAX));
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("approveAndExecute")
+        .arguments_raw(arg_buffer(args))
+        .returns(ExpectError(4, "Insufficient gas for execution"))
+        .run();
+}
+
+#[test]
+fn validate_message_replay() {
+    let mut world = deploy();
+
+    approve_messages(&mut world, &default_signer_set(), &["messageId"]);
+
+    assert!(validate_message(&mut world, "messageId"));
+    assert!(is_message_executed(&mut world, "messageId"));
+
+    // The message can not be validated a second time
+    assert!(!validate_message(&mut world, "messageId"));
+
+    // Nor approved again
+    assert_eq!(
+        approve_messages(&mut world, &default_signer_set(), &["messageId"]),
+        (0, 1)
+    );
+    assert!(!validate_message(&mut world, "messageId"));
+    assert!(!is_message_approved(&mut world, "messageId"));
+}
+
+#[test]
+fn upgrade_from_baseline() {
+    let mut world = deploy_baseline();
+
+    world
+        .tx()
+        .from(OWNER)
+        .to(GATEWAY_ADDRESS)
+        .raw_upgrade()
+        .payment(NotPayable)
+        .code(CODE_PATH)
+        .argument(&ManagedAddress::<StaticApi>::zero())
+        .run();
+
+    // Signers rotated before the upgrade can still sign proofs, without re-registering them
+    assert_eq!(epoch_by_signer_hash(&mut world, &default_signer_set()), 1);
+    assert_eq!(
+        approve_messages(&mut world, &default_signer_set(), &["messageId"]),
+        (1, 0)
+    );
+
+    // And can be stored on chain to be referenced by compact proofs
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("storeSigners")
+        .argument(&default_signer_set().weighted_signers::<StaticApi>())
+        .run();
+
+    let new_signer_set = signer_set(&[4, 5, 6], 1);
+
+    rotate_signers(&mut world, OPERATOR, &default_signer_set(), &new_signer_set);
+
+    assert_eq!(epoch_by_signer_hash(&mut world, &new_signer_set), 2);
+}
+
+#[test]
+fn valid_epochs_test() {
+    let mut world = world();
+
+    world.account(OWNER).nonce(1);
+
+    world
+        .tx()
+        .from(OWNER)
+        .raw_deploy()
+        .code(CODE_PATH)
+        .new_address(GATEWAY_ADDRESS)
+        .argument(&BigUint::<StaticApi>::from(PREVIOUS_SIGNERS_RETENTION))
+        .argument(&ManagedByteArray::<StaticApi, 32>::from(&DOMAIN_SEPARATOR))
+        .argument(&MINIMUM_ROTATION_DELAY)
+        .argument(&OPERATOR)
+        .run();
+
+    // No signers were set yet
+    assert_eq!(valid_epochs(&mut world), None);
+
+    let mut world = deploy();
+
+    assert_eq!(valid_epochs(&mut world), Some((1, 1)));
+
+    let new_signer_set = signer_set(&[4, 5, 6], 1);
+    rotate_signers(&mut world, OPERATOR, &default_signer_set(), &new_signer_set);
+    rotate_signers(
+        &mut world,
+        OPERATOR,
+        &new_signer_set,
+        &signer_set(&[7, 8, 9], 2),
+    );
+
+    assert_eq!(valid_epochs(&mut world), Some((2, 3)));
+}
+
+#[test]
+fn operatorship_transfer() {
+    let mut world = deploy();
+
+    // The operator can only transfer the role through a proposal
+    world
+        .tx()
+        .from(OPERATOR)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("transferOperatorship")
+        .argument(&RELAYER)
+        .returns(ExpectError(4, "Endpoint can only be called by owner"))
+        .run();
+
+    world
+        .tx()
+        .from(OPERATOR)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("proposeOperatorship")
+        .argument(&RELAYER)
+        .run();
+
+    // Upgrading clears the pending proposal
+    world
+        .tx()
+        .from(OWNER)
+        .to(GATEWAY_ADDRESS)
+        .raw_upgrade()
+        .payment(NotPayable)
+        .code(CODE_PATH)
+        .argument(&ManagedAddress::<StaticApi>::zero())
+        .run();
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("acceptOperatorship")
+        .returns(ExpectError(4, "Invalid sender"))
+        .run();
+
+    world
+        .tx()
+        .from(OPERATOR)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("cancelOperatorshipProposal")
+        .returns(ExpectError(4, "No pending operator"))
+        .run();
+}
+
+fn schedule_rotate_signers(world: &mut ScenarioWorld, new_signer_set: &SignerSet) {
+    world
+        .tx()
+        .from(OPERATOR)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("scheduleRotateSigners")
+        .arguments_raw(rotate_signers_args(&default_signer_set(), new_signer_set))
+        .run();
+}
+
+fn cancel_scheduled_rotation_args(
+    new_signer_set: &SignerSet,
+    eta: u64,
+) -> ManagedArgBuffer<StaticApi> {
+    arg_buffer(
+        default_signer_set()
+            .cancel_scheduled_rotation_args::<StaticApi>(&DOMAIN_SEPARATOR, new_signer_set, eta)
+            .unwrap(),
+    )
+}
+
+#[test]
+fn cancel_scheduled_rotation_replay() {
+    let mut world = deploy();
+
+    let new_signer_set = signer_set(&[4, 5, 6], 1);
+
+    schedule_rotate_signers(&mut world, &new_signer_set);
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("cancelScheduledRotationWithProof")
+        .arguments_raw(cancel_scheduled_rotation_args(
+            &new_signer_set,
+            MINIMUM_ROTATION_DELAY,
+        ))
+        .run();
+
+    // The same signers are scheduled again later, with a different eta
+    world.current_block().block_timestamp(100);
+
+    schedule_rotate_signers(&mut world, &new_signer_set);
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("cancelScheduledRotationWithProof")
+        .arguments_raw(cancel_scheduled_rotation_args(
+            &new_signer_set,
+            MINIMUM_ROTATION_DELAY,
+        ))
+        .returns(ExpectError(10, "invalid signature"))
+        .run();
+
+    world
+        .current_block()
+        .block_timestamp(100 + MINIMUM_ROTATION_DELAY);
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("executeScheduledRotation")
+        .run();
+
+    assert_eq!(epoch_by_signer_hash(&mut world, &new_signer_set), 2);
+}
+
+#[test]
+fn scheduled_rotation_rotated_in_directly() {
+    let mut world = deploy();
+
+    let new_signer_set = signer_set(&[4, 5, 6], 1);
+
+    schedule_rotate_signers(&mut world, &new_signer_set);
+
+    rotate_signers(&mut world, OPERATOR, &default_signer_set(), &new_signer_set);
+
+    // The scheduled rotation to the same signers is cleared, so another one can be scheduled
+    world
+        .current_block()
+        .block_timestamp(MINIMUM_ROTATION_DELAY);
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("executeScheduledRotation")
+        .returns(ExpectError(4, "No scheduled rotation"))
+        .run();
+
+    world
+        .tx()
+        .from(OPERATOR)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("scheduleRotateSigners")
+        .arguments_raw(rotate_signers_args(
+            &new_signer_set,
+            &signer_set(&[7, 8, 9], 2),
+        ))
+        .run();
+}
+
+#[test]
+fn schedule_rotation_duplicate_signers() {
+    let mut world = deploy();
+
+    let new_signer_set = signer_set(&[4, 5, 6], 1);
+
+    rotate_signers(&mut world, OPERATOR, &default_signer_set(), &new_signer_set);
+
+    world
+        .tx()
+        .from(OPERATOR)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("scheduleRotateSigners")
+        .arguments_raw(rotate_signers_args(&new_signer_set, &default_signer_set()))
+        .returns(ExpectError(4, "Duplicate signers"))
+        .run();
+
+    world
+        .current_block()
+        .block_timestamp(MINIMUM_ROTATION_DELAY);
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("executeScheduledRotation")
+        .returns(ExpectError(4, "No scheduled rotation"))
+        .run();
+}
+
+fn rotate_domain_separator_args(grace_period: u64) -> ManagedArgBuffer<StaticApi> {
+    let rotation = top_encode_to_vec_u8_or_panic(&DomainSeparatorRotation::<StaticApi> {
+        new_domain_separator: ManagedByteArray::from(&[0x22; 32]),
+        grace_period,
+    });
+
+    let proof = default_signer_set()
+        .proof::<StaticApi>(
+            &DOMAIN_SEPARATOR,
+            &get_data_hash(CommandType::RotateDomainSeparator, &rotation),
+        )
+        .unwrap();
+
+    arg_buffer(vec![rotation, top_encode_to_vec_u8_or_panic(&proof)])
+}
+
+#[test]
+fn rotate_domain_separator_grace_period() {
+    let mut world = deploy();
+
+    world.current_block().block_timestamp(u64::MAX - 1);
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("rotateDomainSeparator")
+        .arguments_raw(rotate_domain_separator_args(u64::MAX))
+        .returns(ExpectError(4, "Invalid grace period"))
+        .run();
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("rotateDomainSeparator")
+        .arguments_raw(rotate_domain_separator_args(
+            MAX_DOMAIN_SEPARATOR_GRACE_PERIOD + 1,
+        ))
+        .returns(ExpectError(4, "Invalid grace period"))
+        .run();
+
+    world.current_block().block_timestamp(1_000);
+
+    world
+        .tx()
+        .from(RELAYER)
+        .to(GATEWAY_ADDRESS)
+        .raw_call("rotateDomainSeparator")
+        .arguments_raw(rotate_domain_separator_args(
+            MAX_DOMAIN_SEPARATOR_GRACE_PERIOD,
+        ))
+        .run();
+
+    world
+        .query()
+        .to(GATEWAY_ADDRESS)
+        .raw_call("previousDomainSeparatorExpiry")
+        .original_result::<u64>()
+        .returns(ExpectValue(1_000 + MAX_DOMAIN_SEPARATOR_GRACE_PERIOD))
+        .run();
+}
+
+#[test]
+fn approve_messages_resubmitted() {
+    let mut world = deploy();
+
+    let message_ids: Vec<String> = (0..10).map(|index| format!("messageId{index}")).collect();
+    let message_ids: Vec<&str> = message_ids.iter().map(String::as_str).collect();
+
+    assert_eq!(
+        approve_messages(&mut world, &default_signer_set(), &message_ids),
+        (10, 0)
+    );
+
+    // Already approved messages are skipped and counted, leaving their state untouched
+    assert_eq!(
+        approve_messages(&mut world, &default_signer_set(), &message_ids),
+        (0, 10)
+    );
+
+    for message_id in message_ids {
+        assert!(is_message_approved(&mut world, message_id));
+    }
+}
@@ -0,0 +1,80 @@
+use gateway::constants::{MessageState, MessageStatus};
+use multiversx_sc::codec::{top_encode_to_vec_u8_or_panic, TopDecode};
+use multiversx_sc::types::{ManagedBuffer, ManagedByteArray};
+use multiversx_sc_scenario::api::StaticApi;
+
+const HASH: [u8; 32] = [0x31; 32];
+
+fn decode(encoded: &[u8]) -> Option<MessageState<StaticApi>> {
+    MessageState::<StaticApi>::top_decode(ManagedBuffer::<StaticApi>::from(encoded)).ok()
+}
+
+#[test]
+fn encode_message_state() {
+    assert!(top_encode_to_vec_u8_or_panic(&MessageState::<StaticApi>::NonExistent).is_empty());
+    assert_eq!(
+        top_encode_to_vec_u8_or_panic(&MessageState::<StaticApi>::Executed),
+        b"1".to_vec()
+    );
+    assert_eq!(
+        top_encode_to_vec_u8_or_panic(&MessageState::<StaticApi>::Approved(
+            ManagedByteArray::from(&HASH)
+        )),
+        HASH.to_vec()
+    );
+}
+
+#[test]
+fn decode_message_state() {
+    assert!(decode(b"") == Some(MessageState::NonExistent));
+    assert!(decode(b"1") == Some(MessageState::Executed));
+    assert!(decode(&HASH) == Some(MessageState::Approved(ManagedByteArray::from(&HASH))));
+}
+
+#[test]
+fn decode_message_state_invalid_hash() {
+    assert!(decode(b"0").is_none());
+    assert!(decode(b"11").is_none());
+    assert!(decode(&HASH[..31]).is_none());
+    assert!(decode(&[HASH.as_slice(), b"1"].concat()).is_none());
+}
+
+// A hash made only of '1' bytes is still a hash, only the single byte "1" marks executed messages
+#[test]
+fn decode_message_state_hash_of_executed_marker() {
+    let hash = [b'1'; 32];
+
+    assert!(decode(&hash) == Some(MessageState::Approved(ManagedByteArray::from(&hash))));
+}
+
+#[test]
+fn message_state_round_trip() {
+    for state in [
+        MessageState::<StaticApi>::NonExistent,
+        MessageState::Approved(ManagedByteArray::from(&HASH)),
+        MessageState::Executed,
+    ] {
+        let encoded = top_encode_to_vec_u8_or_panic(&state);
+
+        assert!(decode(&encoded) == Some(state));
+    }
+}
+
+// Views return the standard enum encoding instead of the storage one
+#[test]
+fn encode_message_status() {
+    assert_eq!(
+        top_encode_to_vec_u8_or_panic(&MessageStatus::<StaticApi>::NonExistent),
+        Vec::<u8>::new()
+    );
+    assert_eq!(
+        top_encode_to_vec_u8_or_panic(&MessageStatus::<StaticApi>::from(MessageState::Approved(
+            ManagedByteArray::from(&HASH)
+        ))),
+        [&[1u8][..], &HASH[..]].concat()
+    );
+    assert_eq!(
+        top_encode_to_vec_u8_or_panic(&MessageStatus::<StaticApi>::Executed),
+        vec![2u8]
+    );
+}
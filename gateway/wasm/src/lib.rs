@@ -6,9 +6,10 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           18
+// Endpoints:                           59
 // Async Callback (empty):               1
-// Total number of exported functions:  21
+// Promise callbacks:                    1
+// Total number of exported functions:  63
 
 #![no_std]
 
@@ -21,23 +22,65 @@ multiversx_sc_wasm_adapter::endpoints! {
         init => init
         upgrade => upgrade
         approveMessages => approve_messages
+        approveMessagesCompact => approve_messages_compact
+        approveAndExecute => approve_and_execute
         rotateSigners => rotate_signers
+        rotateSignersCompact => rotate_signers_compact
+        rotateDomainSeparator => rotate_domain_separator
+        revokeSigners => revoke_signers
+        revokeSignersByHash => revoke_signers_by_hash
+        unrevokeSigners => unrevoke_signers
+        pruneExecutedMessages => prune_executed_messages
+        scheduleRotateSigners => schedule_rotate_signers
+        executeScheduledRotation => execute_scheduled_rotation
+        cancelScheduledRotation => cancel_scheduled_rotation
+        cancelScheduledRotationWithProof => cancel_scheduled_rotation_with_proof
         callContract => call_contract
         validateMessage => validate_message
         isMessageApproved => is_message_approved
+        areMessagesApproved => are_messages_approved
         isMessageExecuted => is_message_executed
+        outboundNonce => outbound_nonce
+        messagesStatus => messages_status
         messages => messages
+        executedMessages => executed_messages
         validateProof => validate_proof
+        validateCompactProof => validate_compact_proof
+        storeSigners => store_signers
+        currentSigners => current_signers
+        validEpochs => valid_epochs
+        signersWeight => signers_weight
         timeSinceRotation => time_since_rotation
         epoch => epoch
         lastRotationTimestamp => last_rotation_timestamp
+        scheduledRotation => scheduled_rotation
         signerHashByEpoch => signer_hash_by_epoch
+        revokedEpochs => revoked_epochs
+        signersByEpoch => signers_by_epoch
         epochBySignerHash => epoch_by_signer_hash
         previousSignersRetention => previous_signers_retention
         domainSeparator => domain_separator
+        previousDomainSeparator => previous_domain_separator
+        previousDomainSeparatorExpiry => previous_domain_separator_expiry
         minimumRotationDelay => minimum_rotation_delay
         transferOperatorship => transfer_operatorship
+        proposeOperatorship => propose_operatorship
+        acceptOperatorship => accept_operatorship
+        cancelOperatorshipProposal => cancel_operatorship_proposal
         operator => operator
+        pendingOperator => pending_operator
+        pause => pause
+        unpause => unpause
+        isPaused => paused
+        setSourceChainAllowlistEnabled => set_source_chain_allowlist_enabled
+        allowSourceChains => allow_source_chains
+        disallowSourceChains => disallow_source_chains
+        setApprovalRateLimit => set_approval_rate_limit
+        isSourceChainAllowlistEnabled => source_chain_allowlist_enabled
+        allowedSourceChains => allowed_source_chains
+        approvalRateLimit => approval_rate_limit
+        approvalWindow => approval_window
+        approve_and_execute_callback => approve_and_execute_callback
     )
 }
 
@@ -5,13 +5,20 @@ import data from '../data.json';
 import { zeroPadValue } from 'ethers';
 import { e } from 'xsuite';
 
+const SIGNER_KEY_TYPE_ED25519 = 0;
+const SIGNER_KEY_TYPE_SECP256K1 = 1;
+
 export async function getCurrentWeightedSigners() {
   const currentSignerSet = await getMultisigProverCurrentSignerSet();
 
   const nonce = zeroPadValue(Buffer.from(BigInt(currentSignerSet.created_at).toString(16), 'hex'), 32).substring(2);
 
   const readableSigners = {
-    signers: currentSignerSet.addresses.map(({ address, weight }) => ({ signer: address, weight: Number(weight) })),
+    signers: currentSignerSet.addresses.map(({ keyType, address, weight }) => ({
+      keyType,
+      signer: address,
+      weight: Number(weight),
+    })),
     threshold: Number(currentSignerSet.threshold),
     nonce,
   };
@@ -23,8 +30,9 @@ export async function getCurrentWeightedSigners() {
 
   const signers = e.Tuple(
     e.List(
-      ...currentSignerSet.addresses.map(({ address, weight }) => e.Tuple(
-        e.TopBuffer(address),
+      ...currentSignerSet.addresses.map(({ keyType, address, weight }) => e.Tuple(
+        e.U8(keyType),
+        e.Buffer(address),
         e.U(BigInt(weight)))
       ),
     ),
@@ -55,10 +63,12 @@ const getMultisigProverCurrentSignerSet = async () => {
 
   const weightedAddresses = signers
     .map((signer: any) => ({
-      address: signer.pub_key.ed25519,
+      keyType: signer.pub_key.ecdsa ? SIGNER_KEY_TYPE_SECP256K1 : SIGNER_KEY_TYPE_ED25519,
+      address: signer.pub_key.ecdsa ?? signer.pub_key.ed25519,
       weight: signer.weight,
     }))
-    .sort((a, b) => a.address.localeCompare(b.address));
+    // Signers are ordered by key type first and then by key
+    .sort((a, b) => a.keyType - b.keyType || a.address.localeCompare(b.address));
 
   return {
     addresses: weightedAddresses,
@@ -6,8 +6,8 @@ use core::marker::PhantomData;
 
 use multiversx_sc::types::ManagedBuffer;
 
-/// Basic Solidity ABI implementation in Rust for MultiversX managed types
-/// Loosely based on https://github.com/rust-ethereum/ethabi
+// Basic Solidity ABI implementation in Rust for MultiversX managed types
+// Loosely based on https://github.com/rust-ethereum/ethabi
 
 pub type Word = [u8; 32];
 
@@ -91,7 +91,7 @@ impl<M: ManagedTypeApi> Token<M> {
 
     fn pad_bytes_len(bytes: &ManagedBuffer<M>) -> u32 {
         // "+ 1" because len is also appended
-        ((bytes.len() + 31) / 32) as u32 + 1
+        bytes.len().div_ceil(32) as u32 + 1
     }
 
     fn pad_bytes_append(data: &mut ManagedBuffer<M>, bytes: &ManagedBuffer<M>) {
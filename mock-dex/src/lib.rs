@@ -0,0 +1,50 @@
+#![no_std]
+
+multiversx_sc::imports!();
+
+// Rates are set in basis points, eg. a rate of 5_000 swaps 2 tokens for 1 EGLD
+pub const RATE_PRECISION: u64 = 10_000;
+
+// Local DEX used for testing the swap adapter of the Gas Service, swapping ESDTs to EGLD at fixed rates
+#[multiversx_sc::contract]
+pub trait MockDex {
+    #[init]
+    fn init(&self) {}
+
+    #[upgrade]
+    fn upgrade(&self) {}
+
+    #[only_owner]
+    #[endpoint(setRate)]
+    fn set_rate(&self, token: TokenIdentifier, rate: BigUint) {
+        self.rates(&token).set(rate);
+    }
+
+    // Adds EGLD liquidity
+    #[payable("EGLD")]
+    #[endpoint(deposit)]
+    fn deposit(&self) {}
+
+    #[payable("*")]
+    #[endpoint(swapTokensToEgld)]
+    fn swap_tokens_to_egld(&self, min_amount_out: BigUint) -> BigUint {
+        let (token, amount) = self.call_value().single_fungible_esdt();
+
+        let rate_mapper = self.rates(&token);
+
+        require!(!rate_mapper.is_empty(), "Token not supported");
+
+        let amount_out = amount * rate_mapper.get() / RATE_PRECISION;
+
+        require!(amount_out >= min_amount_out, "Slippage exceeded");
+
+        self.send()
+            .direct_egld(&self.blockchain().get_caller(), &amount_out);
+
+        amount_out
+    }
+
+    #[view(getRate)]
+    #[storage_mapper("rates")]
+    fn rates(&self, token: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+}
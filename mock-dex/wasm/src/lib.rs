@@ -0,0 +1,30 @@
+// Code generated by the multiversx-sc build system. DO NOT EDIT.
+
+////////////////////////////////////////////////////
+////////////////// AUTO-GENERATED //////////////////
+////////////////////////////////////////////////////
+
+// Init:                                 1
+// Upgrade:                              1
+// Endpoints:                            4
+// Async Callback (empty):               1
+// Total number of exported functions:   7
+
+#![no_std]
+
+multiversx_sc_wasm_adapter::allocator!();
+multiversx_sc_wasm_adapter::panic_handler!();
+
+multiversx_sc_wasm_adapter::endpoints! {
+    mock_dex
+    (
+        init => init
+        upgrade => upgrade
+        setRate => set_rate
+        deposit => deposit
+        swapTokensToEgld => swap_tokens_to_egld
+        getRate => rates
+    )
+}
+
+multiversx_sc_wasm_adapter::async_callback_empty! {}
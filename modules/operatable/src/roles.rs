@@ -8,6 +8,9 @@ bitflags! {
         const MINTER = 0b00000001;
         const OPERATOR = 0b00000010;
         const FLOW_LIMITER = 0b00000100;
+        const REFUNDER = 0b00001000;
+        const COLLECTOR = 0b00010000;
+        const EXPRESS_RELAYER = 0b00100000;
     }
 }
 
@@ -1,6 +1,16 @@
-import { afterEach, beforeEach, test } from 'vitest';
+import { afterEach, assert, beforeEach, test } from 'vitest';
 import { assertAccount, e, LSContract, LSWallet, LSWorld } from 'xsuite';
-import { TOKEN_IDENTIFIER } from '../helpers';
+import {
+  chainGasTotalsKvs,
+  gasPaymentKvs,
+  gasTotalsKvs,
+  getKeccak256Hash,
+  multiTokenGasPaymentKvs,
+  paymentDestinationChainKvs,
+  TOKEN_IDENTIFIER,
+  TOKEN_IDENTIFIER2,
+  TOKEN_IDENTIFIER_EGLD,
+} from '../helpers';
 
 let world: LSWorld;
 let deployer: LSWallet;
@@ -48,7 +58,7 @@ const deployContract = async () => {
   assertAccount(pairs, {
     balance: 0n,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(collector),
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00011000)),
     ],
   });
 };
@@ -73,7 +83,7 @@ test('Pay gas for contract call no esdts', async () => {
   assertAccount(pairs, {
     balance: 0,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
     ],
   });
 });
@@ -101,7 +111,12 @@ test('Pay gas for contract call', async () => {
   assertAccount(pairs, {
     balance: 0,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, TOKEN_IDENTIFIER, 1_000, e.Addr(deployer.toString())),
+      paymentDestinationChainKvs(1, 'ethereum'),
+      gasTotalsKvs(TOKEN_IDENTIFIER, 1_000),
+      chainGasTotalsKvs('ethereum', TOKEN_IDENTIFIER, 1_000),
 
       e.kvs.Esdts([
         {
@@ -133,7 +148,7 @@ test('Pay native gas for contract call no value', async () => {
   assertAccount(pairs, {
     balance: 0,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
     ],
   });
 });
@@ -159,7 +174,222 @@ test('Pay native gas for contract call', async () => {
   assertAccount(pairs, {
     balance: 1_000,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, 'EGLD', 1_000, e.Addr(deployer.toString())),
+      paymentDestinationChainKvs(1, 'ethereum'),
+      gasTotalsKvs('EGLD', 1_000),
+      chainGasTotalsKvs('ethereum', 'EGLD', 1_000),
+    ],
+  });
+});
+
+const createMultiTokenWallet = () => world.createWallet({
+  balance: 10_000_000_000n,
+  kvs: [
+    e.kvs.Esdts([
+      { id: TOKEN_IDENTIFIER, amount: 100_000 },
+      { id: TOKEN_IDENTIFIER2, amount: 100_000 },
+    ]),
+  ],
+});
+
+test('Pay multi token gas for contract call nothing received', async () => {
+  await deployContract();
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'payMultiTokenGasForContractCall',
+    funcArgs: [
+      deployer,
+      e.Str('ethereum'),
+      e.Str('mockAddress'),
+      e.Str('payload'),
+      e.Addr(deployer.toString()),
+    ],
+  }).assertFail({ code: 4, message: 'Nothing received' });
+});
+
+test('Pay multi token gas for contract call duplicate token', async () => {
+  await deployContract();
+
+  const user = await createMultiTokenWallet();
+
+  await user.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'payMultiTokenGasForContractCall',
+    funcArgs: [
+      user,
+      e.Str('ethereum'),
+      e.Str('mockAddress'),
+      e.Str('payload'),
+      e.Addr(user.toString()),
+    ],
+    esdts: [
+      { id: TOKEN_IDENTIFIER, amount: 1_000 },
+      { id: TOKEN_IDENTIFIER, amount: 500 },
+    ],
+  }).assertFail({ code: 4, message: 'Duplicate token' });
+});
+
+test('Pay multi token gas for contract call', async () => {
+  await deployContract();
+
+  const user = await createMultiTokenWallet();
+
+  await user.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'payMultiTokenGasForContractCall',
+    funcArgs: [
+      user,
+      e.Str('ethereum'),
+      e.Str('mockAddress'),
+      e.Str('payload'),
+      e.Addr(user.toString()),
+    ],
+    esdts: [
+      { id: TOKEN_IDENTIFIER, amount: 1_000 },
+      { id: TOKEN_IDENTIFIER2, amount: 500 },
+      { id: TOKEN_IDENTIFIER_EGLD, amount: 100 },
+    ],
+  });
+
+  let pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 100,
+    kvs: [
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      multiTokenGasPaymentKvs(
+        1,
+        [
+          { id: TOKEN_IDENTIFIER, amount: 1_000 },
+          { id: TOKEN_IDENTIFIER2, amount: 500 },
+          { id: 'EGLD', amount: 100 },
+        ],
+        e.Addr(user.toString()),
+      ),
+      paymentDestinationChainKvs(1, 'ethereum'),
+      gasTotalsKvs(TOKEN_IDENTIFIER, 1_000),
+      gasTotalsKvs(TOKEN_IDENTIFIER2, 500),
+      gasTotalsKvs('EGLD', 100),
+      chainGasTotalsKvs('ethereum', TOKEN_IDENTIFIER, 1_000),
+      chainGasTotalsKvs('ethereum', TOKEN_IDENTIFIER2, 500),
+      chainGasTotalsKvs('ethereum', 'EGLD', 100),
+      e.kvs.Esdts([
+        { id: TOKEN_IDENTIFIER, amount: 1_000 },
+        { id: TOKEN_IDENTIFIER2, amount: 500 },
+      ]),
+    ],
+  });
+});
+
+test('Pay multi token gas for contract call below min gas fee', async () => {
+  await deployContract();
+
+  const user = await createMultiTokenWallet();
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'setGasFee',
+    funcArgs: [
+      e.Str('ethereum'),
+      e.Str(TOKEN_IDENTIFIER2),
+      e.U(1_000),
+      e.U(0),
+    ],
+  });
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'setGasFee',
+    funcArgs: [
+      e.Str('ethereum'),
+      e.Str(TOKEN_IDENTIFIER),
+      e.U(1_000),
+      e.U(0),
+    ],
+  });
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'setMinGasFeeEnforced',
+    funcArgs: [
+      e.Bool(true),
+    ],
+  });
+
+  // Neither token covers its quote on its own
+  await user.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'payMultiTokenGasForContractCall',
+    funcArgs: [
+      user,
+      e.Str('ethereum'),
+      e.Str('mockAddress'),
+      e.Str('payload'),
+      e.Addr(user.toString()),
+    ],
+    esdts: [
+      { id: TOKEN_IDENTIFIER, amount: 999 },
+      { id: TOKEN_IDENTIFIER2, amount: 999 },
+    ],
+  }).assertFail({ code: 4, message: 'Insufficient gas fee' });
+
+  // A token without a fee set can't cover the minimum
+  await user.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'payMultiTokenGasForContractCall',
+    funcArgs: [
+      user,
+      e.Str('ethereum'),
+      e.Str('mockAddress'),
+      e.Str('payload'),
+      e.Addr(user.toString()),
+    ],
+    esdts: [
+      { id: TOKEN_IDENTIFIER, amount: 999 },
+      { id: TOKEN_IDENTIFIER_EGLD, amount: 1_000 },
+    ],
+  }).assertFail({ code: 4, message: 'Gas fee not set' });
+
+  await user.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'payMultiTokenGasForContractCall',
+    funcArgs: [
+      user,
+      e.Str('ethereum'),
+      e.Str('mockAddress'),
+      e.Str('payload'),
+      e.Addr(user.toString()),
+    ],
+    esdts: [
+      { id: TOKEN_IDENTIFIER, amount: 1_000 },
+      { id: TOKEN_IDENTIFIER_EGLD, amount: 1_000 },
+    ],
+  }).assertFail({ code: 4, message: 'Gas fee not set' });
+
+  await user.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'payMultiTokenGasForContractCall',
+    funcArgs: [
+      user,
+      e.Str('ethereum'),
+      e.Str('mockAddress'),
+      e.Str('payload'),
+      e.Addr(user.toString()),
+    ],
+    esdts: [
+      { id: TOKEN_IDENTIFIER, amount: 999 },
+      { id: TOKEN_IDENTIFIER2, amount: 1_000 },
     ],
   });
 });
@@ -184,7 +414,7 @@ test('Pay gas for express contract call no esdts', async () => {
   assertAccount(pairs, {
     balance: 0,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
     ],
   });
 });
@@ -212,7 +442,13 @@ test('Pay gas for express contract call', async () => {
   assertAccount(pairs, {
     balance: 0,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, TOKEN_IDENTIFIER, 1_000, e.Addr(deployer.toString()), 0, false, true),
+      paymentDestinationChainKvs(1, 'ethereum'),
+      gasTotalsKvs(TOKEN_IDENTIFIER, 1_000),
+      chainGasTotalsKvs('ethereum', TOKEN_IDENTIFIER, 1_000),
+      e.kvs.Mapper('express_escrow', e.Str(TOKEN_IDENTIFIER)).Value(e.U(1_000)),
 
       e.kvs.Esdts([
         {
@@ -244,7 +480,7 @@ test('Pay native gas for express contract call no value', async () => {
   assertAccount(pairs, {
     balance: 0,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
     ],
   });
 });
@@ -270,7 +506,13 @@ test('Pay native gas for express contract call', async () => {
   assertAccount(pairs, {
     balance: 1_000,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, 'EGLD', 1_000, e.Addr(deployer.toString()), 0, false, true),
+      paymentDestinationChainKvs(1, 'ethereum'),
+      gasTotalsKvs('EGLD', 1_000),
+      chainGasTotalsKvs('ethereum', 'EGLD', 1_000),
+      e.kvs.Mapper('express_escrow', e.Str('EGLD')).Value(e.U(1_000)),
     ],
   });
 });
@@ -293,7 +535,7 @@ test('Add gas no esdts', async () => {
   assertAccount(pairs, {
     balance: 0,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
     ],
   });
 });
@@ -319,7 +561,10 @@ test('Add gas', async () => {
   assertAccount(pairs, {
     balance: 0,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, TOKEN_IDENTIFIER, 1_000, e.Addr(deployer.toString())),
+      gasTotalsKvs(TOKEN_IDENTIFIER, 1_000),
 
       e.kvs.Esdts([
         {
@@ -349,7 +594,7 @@ test('Add native gas no value', async () => {
   assertAccount(pairs, {
     balance: 0,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
     ],
   });
 });
@@ -373,7 +618,10 @@ test('Add native gas', async () => {
   assertAccount(pairs, {
     balance: 1_000,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, 'EGLD', 1_000, e.Addr(deployer.toString())),
+      gasTotalsKvs('EGLD', 1_000),
     ],
   });
 });
@@ -396,7 +644,7 @@ test('Add express gas no esdts', async () => {
   assertAccount(pairs, {
     balance: 0,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
     ],
   });
 });
@@ -422,7 +670,11 @@ test('Add express gas', async () => {
   assertAccount(pairs, {
     balance: 0,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, TOKEN_IDENTIFIER, 1_000, e.Addr(deployer.toString()), 0, false, true),
+      gasTotalsKvs(TOKEN_IDENTIFIER, 1_000),
+      e.kvs.Mapper('express_escrow', e.Str(TOKEN_IDENTIFIER)).Value(e.U(1_000)),
 
       e.kvs.Esdts([
         {
@@ -452,7 +704,7 @@ test('Add native express gas no value', async () => {
   assertAccount(pairs, {
     balance: 0,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
     ],
   });
 });
@@ -476,7 +728,38 @@ test('Add native express gas', async () => {
   assertAccount(pairs, {
     balance: 1_000,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, 'EGLD', 1_000, e.Addr(deployer.toString()), 0, false, true),
+      gasTotalsKvs('EGLD', 1_000),
+      e.kvs.Mapper('express_escrow', e.Str('EGLD')).Value(e.U(1_000)),
+    ],
+  });
+});
+
+test('Add multi token gas', async () => {
+  await deployContract();
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'addMultiTokenGas',
+    funcArgs: [
+      e.Str('txHash'),
+      e.U(10),
+      e.Addr(deployer.toString()),
+    ],
+    value: 1_000,
+  });
+
+  let pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 1_000,
+    kvs: [
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, 'EGLD', 1_000, e.Addr(deployer.toString())),
+      gasTotalsKvs('EGLD', 1_000),
     ],
   });
 });
@@ -565,7 +848,7 @@ test('Collect fees', async () => {
     ...(await contract.getAccount()),
     balance: 2_000,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
 
       e.kvs.Esdts([
         {
@@ -597,7 +880,9 @@ test('Collect fees', async () => {
   assertAccount(pairs, {
     balance: 0,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      gasTotalsKvs(TOKEN_IDENTIFIER, 0, 0, 1_000),
+      gasTotalsKvs('EGLD', 0, 0, 2_000),
     ],
   });
 
@@ -622,7 +907,7 @@ test('Collect fees too much asked', async () => {
     ...(await contract.getAccount()),
     balance: 2_000,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
 
       e.kvs.Esdts([
         {
@@ -633,7 +918,7 @@ test('Collect fees too much asked', async () => {
     ],
   });
 
-  await collector.callContract({
+  let result = await collector.callContract({
     callee: contract,
     gasLimit: 10_000_000,
     funcName: 'collectFees',
@@ -647,12 +932,15 @@ test('Collect fees too much asked', async () => {
 
       e.U32(3),
       e.U(750),
-      e.U(750), // Higher than remaining balance, will be ignored
+      e.U(750), // Higher than remaining balance, will be skipped
       e.U(20_000),
     ],
   });
 
-  await collector.callContract({
+  // Success, then insufficient balance skips
+  assert.deepEqual(result.returnData, ['', '05', '05']);
+
+  result = await collector.callContract({
     callee: contract,
     gasLimit: 10_000_000,
     funcName: 'collectFees',
@@ -669,11 +957,14 @@ test('Collect fees too much asked', async () => {
     ],
   });
 
+  assert.deepEqual(result.returnData, ['05', '05']);
+
   let pairs = await contract.getAccount();
   assertAccount(pairs, {
     balance: 2_000,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      gasTotalsKvs(TOKEN_IDENTIFIER, 0, 0, 750),
 
       e.kvs.Esdts([
         {
@@ -685,6 +976,22 @@ test('Collect fees too much asked', async () => {
   });
 });
 
+const payNativeGas = async (value: number) => {
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'payNativeGasForContractCall',
+    funcArgs: [
+      deployer,
+      e.Str('ethereum'),
+      e.Str('mockAddress'),
+      e.Str('payload'),
+      e.Addr(deployer.toString()),
+    ],
+    value,
+  });
+};
+
 test('Refund not collector', async () => {
   await deployContract();
 
@@ -695,14 +1002,13 @@ test('Refund not collector', async () => {
     funcArgs: [
       e.Str('txHash'),
       e.U(1),
-      e.Addr(deployer.toString()),
-      e.Str(TOKEN_IDENTIFIER),
+      e.U64(1),
       e.U(1_000),
     ],
   }).assertFail({ code: 4, message: 'Not collector' });
 });
 
-test('Refund invalid address', async () => {
+test('Refund invalid payment', async () => {
   await deployContract();
 
   await collector.callContract({
@@ -712,23 +1018,34 @@ test('Refund invalid address', async () => {
     funcArgs: [
       e.Str('txHash'),
       e.U(1),
-      e.TopBuffer('0000000000000000000000000000000000000000000000000000000000000000'),
-      e.Str(TOKEN_IDENTIFIER),
+      e.U64(1),
       e.U(1_000),
     ],
-  }).assertFail({ code: 4, message: 'Invalid address' });
+  }).assertFail({ code: 4, message: 'Invalid payment' });
 });
 
-test('Refund egld', async () => {
+test('Refund exceeds payment', async () => {
   await deployContract();
 
-  await contract.setAccount({
-    ...(await contract.getAccount()),
-    balance: 2_000,
-    kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+  await payNativeGas(1_000);
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'refund',
+    funcArgs: [
+      e.Str('txHash'),
+      e.U(1),
+      e.U64(1),
+      e.U(1_001),
     ],
-  });
+  }).assertFail({ code: 4, message: 'Refund exceeds payment' });
+});
+
+test('Refund egld', async () => {
+  await deployContract();
+
+  await payNativeGas(1_000);
 
   await collector.callContract({
     callee: contract,
@@ -737,40 +1054,57 @@ test('Refund egld', async () => {
     funcArgs: [
       e.Str('txHash'),
       e.U(1),
-      e.Addr(deployer.toString()),
-      e.Str('EGLD'),
+      e.U64(1),
       e.U(500),
     ],
   });
 
   let pairs = await contract.getAccount();
   assertAccount(pairs, {
-    balance: 1_500,
+    balance: 500,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, 'EGLD', 1_000, e.Addr(deployer.toString()), 500, true),
+      paymentDestinationChainKvs(1, 'ethereum'),
+      gasTotalsKvs('EGLD', 1_000, 500),
+      chainGasTotalsKvs('ethereum', 'EGLD', 1_000, 500),
     ],
   });
 
   let pairsDeployer = await deployer.getAccount();
   assertAccount(pairsDeployer, {
-    balance: 10_000_000_500,
+    balance: 9_999_999_500,
   });
+
+  // Settled payments can not be refunded again
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'refund',
+    funcArgs: [
+      e.Str('txHash'),
+      e.U(1),
+      e.U64(1),
+      e.U(500),
+    ],
+  }).assertFail({ code: 4, message: 'Payment already settled' });
 });
 
 test('Refund esdt', async () => {
   await deployContract();
 
-  await contract.setAccount({
-    ...(await contract.getAccount()),
-    kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
-
-      e.kvs.Esdts([
-        {
-          id: TOKEN_IDENTIFIER,
-          amount: 2_000,
-        },
-      ]),
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'addGas',
+    funcArgs: [
+      e.Str('txHash'),
+      e.U(10),
+      e.Addr(deployer.toString()),
+    ],
+    esdts: [
+      { id: TOKEN_IDENTIFIER, amount: 1_000 },
     ],
   });
 
@@ -780,9 +1114,8 @@ test('Refund esdt', async () => {
     funcName: 'refund',
     funcArgs: [
       e.Str('txHash'),
-      e.U(1),
-      e.Addr(deployer.toString()),
-      e.Str(TOKEN_IDENTIFIER),
+      e.U(10),
+      e.U64(1),
       e.U(500),
     ],
   });
@@ -790,12 +1123,15 @@ test('Refund esdt', async () => {
   let pairs = await contract.getAccount();
   assertAccount(pairs, {
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString())),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, TOKEN_IDENTIFIER, 1_000, e.Addr(deployer.toString()), 500, true),
+      gasTotalsKvs(TOKEN_IDENTIFIER, 1_000, 500),
 
       e.kvs.Esdts([
         {
           id: TOKEN_IDENTIFIER,
-          amount: 1_500,
+          amount: 500,
         },
       ]),
     ],
@@ -808,53 +1144,999 @@ test('Refund esdt', async () => {
       e.kvs.Esdts([
         {
           id: TOKEN_IDENTIFIER,
-          amount: 100_500,
+          amount: 99_500,
         },
       ]),
     ],
   });
 });
 
-test('Set gas collector not collector', async () => {
+test('Refund nothing settles payment', async () => {
   await deployContract();
 
-  let user = await world.createWallet();
-
-  await user.callContract({
-    callee: contract,
-    gasLimit: 10_000_000,
-    funcName: 'setGasCollector',
-    funcArgs: [
-      deployer,
-    ],
-  }).assertFail({ code: 4, message: 'Not collector or owner' });
-});
-
-test('Set gas collector', async () => {
-  await deployContract();
+  await payNativeGas(1_000);
 
-  // Deployer can also set collector
-  await deployer.callContract({
+  await collector.callContract({
     callee: contract,
     gasLimit: 10_000_000,
-    funcName: 'setGasCollector',
+    funcName: 'refund',
     funcArgs: [
-      collector,
+      e.Str('txHash'),
+      e.U(1),
+      e.U64(1),
+      e.U(0),
     ],
   });
 
   let pairs = await contract.getAccount();
   assertAccount(pairs, {
-    balance: 0n,
+    balance: 1_000,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(collector),
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, 'EGLD', 1_000, e.Addr(deployer.toString()), 0, true),
+      paymentDestinationChainKvs(1, 'ethereum'),
+      gasTotalsKvs('EGLD', 1_000),
+      chainGasTotalsKvs('ethereum', 'EGLD', 1_000),
     ],
   });
+});
 
-  await collector.callContract({
-    callee: contract,
-    gasLimit: 10_000_000,
-    funcName: 'setGasCollector',
+test('Refund multi token', async () => {
+  await deployContract();
+
+  const user = await createMultiTokenWallet();
+
+  await user.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'addMultiTokenGas',
+    funcArgs: [
+      e.Str('txHash'),
+      e.U(10),
+      e.Addr(user.toString()),
+    ],
+    esdts: [
+      { id: TOKEN_IDENTIFIER, amount: 1_000 },
+      { id: TOKEN_IDENTIFIER2, amount: 500 },
+    ],
+  });
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'refund',
+    funcArgs: [
+      e.Str('txHash'),
+      e.U(1),
+      e.U64(1),
+      e.U(100),
+    ],
+  }).assertFail({ code: 4, message: 'Invalid amounts' });
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'refund',
+    funcArgs: [
+      e.Str('txHash'),
+      e.U(1),
+      e.U64(1),
+      e.U(0),
+      e.U(501),
+    ],
+  }).assertFail({ code: 4, message: 'Refund exceeds payment' });
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'refund',
+    funcArgs: [
+      e.Str('txHash'),
+      e.U(1),
+      e.U64(1),
+      e.U(0),
+      e.U(200),
+    ],
+  });
+
+  let pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 0,
+    kvs: [
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      multiTokenGasPaymentKvs(
+        1,
+        [
+          { id: TOKEN_IDENTIFIER, amount: 1_000 },
+          { id: TOKEN_IDENTIFIER2, amount: 500 },
+        ],
+        e.Addr(user.toString()),
+        [0, 200],
+        true,
+      ),
+      gasTotalsKvs(TOKEN_IDENTIFIER, 1_000),
+      gasTotalsKvs(TOKEN_IDENTIFIER2, 500, 200),
+      e.kvs.Esdts([
+        { id: TOKEN_IDENTIFIER, amount: 1_000 },
+        { id: TOKEN_IDENTIFIER2, amount: 300 },
+      ]),
+    ],
+  });
+
+  pairs = await user.getAccount();
+  assertAccount(pairs, {
+    hasKvs: [
+      e.kvs.Esdts([
+        { id: TOKEN_IDENTIFIER, amount: 99_000 },
+        { id: TOKEN_IDENTIFIER2, amount: 99_700 },
+      ]),
+    ],
+  });
+});
+
+test('Batch refund', async () => {
+  await deployContract();
+
+  await payNativeGas(1_000);
+  await payNativeGas(500);
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'batchRefund',
+    funcArgs: [
+      e.Str('txHash'),
+      e.U(1),
+      e.U64(1),
+      e.List(e.U(100)),
+    ],
+  }).assertFail({ code: 4, message: 'Not collector' });
+
+  const result = await collector.callContract({
+    callee: contract,
+    gasLimit: 20_000_000,
+    funcName: 'batchRefund',
+    funcArgs: [
+      e.Str('txHash'),
+      e.U(1),
+      e.U64(1),
+      e.List(e.U(100)),
+
+      e.Str('txHash'),
+      e.U(1),
+      e.U64(1), // Already settled by the previous entry
+      e.List(e.U(100)),
+
+      e.Str('txHash2'),
+      e.U(2),
+      e.U64(2),
+      e.List(e.U(501)),
+
+      e.Str('txHash2'),
+      e.U(2),
+      e.U64(2),
+      e.List(e.U(100), e.U(100)),
+
+      e.Str('txHash3'),
+      e.U(3),
+      e.U64(3),
+      e.List(e.U(100)),
+    ],
+  });
+
+  // Success, payment already settled, refund exceeds payment, invalid amounts, invalid payment
+  assert.deepEqual(result.returnData, ['', '02', '04', '03', '01']);
+
+  let pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 1_400,
+    kvs: [
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(2)),
+      gasPaymentKvs(1, 'EGLD', 1_000, e.Addr(deployer.toString()), 100, true),
+      gasPaymentKvs(2, 'EGLD', 500, e.Addr(deployer.toString())),
+      paymentDestinationChainKvs(1, 'ethereum'),
+      paymentDestinationChainKvs(2, 'ethereum'),
+      gasTotalsKvs('EGLD', 1_500, 100),
+      chainGasTotalsKvs('ethereum', 'EGLD', 1_500, 100),
+    ],
+  });
+
+  pairs = await deployer.getAccount();
+  assertAccount(pairs, {
+    balance: 9_999_998_600n,
+  });
+});
+
+test('Batch refund insufficient balance', async () => {
+  await deployContract();
+
+  await payNativeGas(1_000);
+
+  // Fees were collected before the refund
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'collectFees',
+    funcArgs: [
+      e.Addr(collector.toString()),
+      e.U32(1),
+      e.Str('EGLD'),
+      e.U32(1),
+      e.U(1_000),
+    ],
+  });
+
+  const result = await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'batchRefund',
+    funcArgs: [
+      e.Str('txHash'),
+      e.U(1),
+      e.U64(1),
+      e.List(e.U(100)),
+    ],
+  });
+
+  assert.deepEqual(result.returnData, ['05']);
+
+  let pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 0,
+    kvs: [
+      e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, 'EGLD', 1_000, e.Addr(deployer.toString())),
+      paymentDestinationChainKvs(1, 'ethereum'),
+      gasTotalsKvs('EGLD', 1_000, 0, 1_000),
+      chainGasTotalsKvs('ethereum', 'EGLD', 1_000),
+    ],
+  });
+});
+
+const COMMAND_ID = getKeccak256Hash('commandId');
+
+const payNativeExpressGas = async (value: number) => {
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'payNativeGasForExpressCall',
+    funcArgs: [
+      deployer,
+      e.Str('ethereum'),
+      e.Str('mockAddress'),
+      e.Str('payload'),
+      e.Addr(deployer.toString()),
+    ],
+    value,
+  });
+};
+
+const createExpressRelayer = async () => {
+  const relayer = await world.createWallet();
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'addExpressRelayer',
+    funcArgs: [
+      relayer,
+    ],
+  });
+
+  return relayer;
+};
+
+test('Register express call errors', async () => {
+  await deployContract();
+
+  const relayer = await createExpressRelayer();
+
+  await payNativeGas(1_000);
+  await payNativeExpressGas(1_000);
+
+  // Only express relayers can register express calls
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'registerExpressCall',
+    funcArgs: [
+      e.TopBuffer(COMMAND_ID),
+      e.U64(2),
+    ],
+  }).assertFail({ code: 4, message: 'Not express relayer' });
+
+  await relayer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'registerExpressCall',
+    funcArgs: [
+      e.TopBuffer(COMMAND_ID),
+      e.U64(3),
+    ],
+  }).assertFail({ code: 4, message: 'Invalid payment' });
+
+  await relayer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'registerExpressCall',
+    funcArgs: [
+      e.TopBuffer(COMMAND_ID),
+      e.U64(1),
+    ],
+  }).assertFail({ code: 4, message: 'Not express payment' });
+
+  await relayer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'registerExpressCall',
+    funcArgs: [
+      e.TopBuffer(COMMAND_ID),
+      e.U64(2),
+    ],
+  });
+
+  await relayer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'registerExpressCall',
+    funcArgs: [
+      e.TopBuffer(COMMAND_ID),
+      e.U64(2),
+    ],
+  }).assertFail({ code: 4, message: 'Express call already registered' });
+
+  await relayer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'registerExpressCall',
+    funcArgs: [
+      e.TopBuffer(getKeccak256Hash('otherCommandId')),
+      e.U64(2),
+    ],
+  }).assertFail({ code: 4, message: 'Express payment already used' });
+
+  // Pending express calls can not be refunded
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'refund',
+    funcArgs: [
+      e.Str('txHash'),
+      e.U(1),
+      e.U64(2),
+      e.U(1_000),
+    ],
+  }).assertFail({ code: 4, message: 'Express call pending' });
+});
+
+test('Reimburse express call', async () => {
+  await deployContract();
+
+  const relayer = await createExpressRelayer();
+
+  await payNativeExpressGas(1_000);
+
+  // Express gas is escrowed and can not be collected
+  let result = await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'collectFees',
+    funcArgs: [
+      e.Addr(collector.toString()),
+      e.U32(1),
+      e.Str('EGLD'),
+      e.U32(1),
+      e.U(1),
+    ],
+  });
+  assert.deepEqual(result.returnData, ['05']);
+
+  await relayer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'registerExpressCall',
+    funcArgs: [
+      e.TopBuffer(COMMAND_ID),
+      e.U64(1),
+    ],
+  });
+
+  let pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 1_000,
+    kvs: [
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('account_roles', relayer).Value(e.U32(0b00100000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, 'EGLD', 1_000, e.Addr(deployer.toString()), 0, false, true),
+      paymentDestinationChainKvs(1, 'ethereum'),
+      gasTotalsKvs('EGLD', 1_000),
+      chainGasTotalsKvs('ethereum', 'EGLD', 1_000),
+      e.kvs.Mapper('express_escrow', e.Str('EGLD')).Value(e.U(1_000)),
+      e.kvs.Mapper('payment_express_call', e.U64(1)).Value(e.TopBuffer(COMMAND_ID)),
+      e.kvs.Mapper('express_calls', e.TopBuffer(COMMAND_ID)).Value(e.Tuple(e.U64(1), relayer, e.Bool(false))),
+    ],
+  });
+
+  await relayer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'reimburseExpressCall',
+    funcArgs: [
+      e.TopBuffer(COMMAND_ID),
+    ],
+  }).assertFail({ code: 4, message: 'Not refunder' });
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'reimburseExpressCall',
+    funcArgs: [
+      e.TopBuffer(getKeccak256Hash('otherCommandId')),
+    ],
+  }).assertFail({ code: 4, message: 'Invalid express call' });
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'reimburseExpressCall',
+    funcArgs: [
+      e.TopBuffer(COMMAND_ID),
+    ],
+  });
+
+  pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 0,
+    kvs: [
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('account_roles', relayer).Value(e.U32(0b00100000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, 'EGLD', 1_000, e.Addr(deployer.toString()), 0, true, true),
+      paymentDestinationChainKvs(1, 'ethereum'),
+      gasTotalsKvs('EGLD', 1_000, 0, 0, 1_000),
+      chainGasTotalsKvs('ethereum', 'EGLD', 1_000),
+      e.kvs.Mapper('express_calls', e.TopBuffer(COMMAND_ID)).Value(e.Tuple(e.U64(1), relayer, e.Bool(true))),
+    ],
+  });
+
+  pairs = await relayer.getAccount();
+  assertAccount(pairs, {
+    balance: 1_000,
+  });
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'reimburseExpressCall',
+    funcArgs: [
+      e.TopBuffer(COMMAND_ID),
+    ],
+  }).assertFail({ code: 4, message: 'Express call already reimbursed' });
+
+  // Command can not be fronted again
+  await relayer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'registerExpressCall',
+    funcArgs: [
+      e.TopBuffer(COMMAND_ID),
+      e.U64(1),
+    ],
+  }).assertFail({ code: 4, message: 'Express call already registered' });
+});
+
+test('Cancel express call and refund', async () => {
+  await deployContract();
+
+  const relayer = await createExpressRelayer();
+
+  await payNativeExpressGas(1_000);
+
+  await relayer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'registerExpressCall',
+    funcArgs: [
+      e.TopBuffer(COMMAND_ID),
+      e.U64(1),
+    ],
+  });
+
+  await relayer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'cancelExpressCall',
+    funcArgs: [
+      e.TopBuffer(COMMAND_ID),
+    ],
+  }).assertFail({ code: 4, message: 'Not refunder' });
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'cancelExpressCall',
+    funcArgs: [
+      e.TopBuffer(COMMAND_ID),
+    ],
+  });
+
+  // Unused express payment can be refunded
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'refund',
+    funcArgs: [
+      e.Str('txHash'),
+      e.U(1),
+      e.U64(1),
+      e.U(600),
+    ],
+  });
+
+  let pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 400,
+    kvs: [
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('account_roles', relayer).Value(e.U32(0b00100000)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+      gasPaymentKvs(1, 'EGLD', 1_000, e.Addr(deployer.toString()), 600, true, true),
+      paymentDestinationChainKvs(1, 'ethereum'),
+      gasTotalsKvs('EGLD', 1_000, 600),
+      chainGasTotalsKvs('ethereum', 'EGLD', 1_000, 600),
+    ],
+  });
+
+  // Once the express payment is settled, the rest can be collected as fees
+  const result = await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'collectFees',
+    funcArgs: [
+      e.Addr(collector.toString()),
+      e.U32(1),
+      e.Str('EGLD'),
+      e.U32(1),
+      e.U(400),
+    ],
+  });
+  assert.deepEqual(result.returnData, ['']);
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'cancelExpressCall',
+    funcArgs: [
+      e.TopBuffer(COMMAND_ID),
+    ],
+  }).assertFail({ code: 4, message: 'Invalid express call' });
+});
+
+test('Set gas fee not collector', async () => {
+  await deployContract();
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'setGasFee',
+    funcArgs: [
+      e.Str('ethereum'),
+      e.Str('EGLD'),
+      e.U(1_000),
+      e.U(10),
+    ],
+  }).assertFail({ code: 4, message: 'Not collector' });
+});
+
+test('Set gas fee', async () => {
+  await deployContract();
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'setGasFee',
+    funcArgs: [
+      e.Str(''),
+      e.Str('EGLD'),
+      e.U(1_000),
+      e.U(10),
+    ],
+  }).assertFail({ code: 4, message: 'Invalid destination chain' });
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'setGasFee',
+    funcArgs: [
+      e.Str('ethereum'),
+      e.Str('EGLD'),
+      e.U(1_000),
+      e.U(10),
+    ],
+  });
+
+  let pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 0n,
+    kvs: [
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('gas_fees', e.Str('ethereum'), e.Str('EGLD')).Value(e.Tuple(e.U(1_000), e.U(10))),
+    ],
+  });
+
+  // 1_000 + 7 bytes * 10
+  let query = await world.query({
+    callee: contract,
+    funcName: 'estimateGasFee',
+    funcArgs: [e.Str('ethereum'), e.U64(7), e.Str('EGLD')],
+  });
+  assert(query.returnData[0] === '042e');
+
+  // No fee set for other tokens
+  query = await world.query({
+    callee: contract,
+    funcName: 'estimateGasFee',
+    funcArgs: [e.Str('ethereum'), e.U64(7), e.Str(TOKEN_IDENTIFIER)],
+  });
+  assert(query.returnData[0] === '');
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'removeGasFee',
+    funcArgs: [
+      e.Str('ethereum'),
+      e.Str('EGLD'),
+    ],
+  });
+
+  pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 0n,
+    kvs: [
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00011000)),
+    ],
+  });
+});
+
+test('Pay gas below min gas fee', async () => {
+  await deployContract();
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'setGasFee',
+    funcArgs: [
+      e.Str('ethereum'),
+      e.Str('EGLD'),
+      e.U(1_000),
+      e.U(10),
+    ],
+  });
+
+  // Not rejected unless enforced
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'payNativeGasForContractCall',
+    value: 1_000,
+    funcArgs: [
+      deployer,
+      e.Str('ethereum'),
+      e.Str('mockAddress'),
+      e.Str('payload'),
+      e.Addr(deployer.toString()),
+    ],
+  });
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'setMinGasFeeEnforced',
+    funcArgs: [
+      e.Bool(true),
+    ],
+  });
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'payNativeGasForContractCall',
+    value: 1_069,
+    funcArgs: [
+      deployer,
+      e.Str('ethereum'),
+      e.Str('mockAddress'),
+      e.Str('payload'),
+      e.Addr(deployer.toString()),
+    ],
+  }).assertFail({ code: 4, message: 'Insufficient gas fee' });
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'payNativeGasForExpressCall',
+    value: 1_069,
+    funcArgs: [
+      deployer,
+      e.Str('ethereum'),
+      e.Str('mockAddress'),
+      e.Str('payload'),
+      e.Addr(deployer.toString()),
+    ],
+  }).assertFail({ code: 4, message: 'Insufficient gas fee' });
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'payNativeGasForContractCall',
+    value: 1_070,
+    funcArgs: [
+      deployer,
+      e.Str('ethereum'),
+      e.Str('mockAddress'),
+      e.Str('payload'),
+      e.Addr(deployer.toString()),
+    ],
+  });
+
+  // Tokens without a fee set have no known minimum, so they are rejected
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'payGasForContractCall',
+    funcArgs: [
+      deployer,
+      e.Str('ethereum'),
+      e.Str('mockAddress'),
+      e.Str('payload'),
+      e.Addr(deployer.toString()),
+    ],
+    esdts: [{ id: TOKEN_IDENTIFIER, amount: 10_000 }],
+  }).assertFail({ code: 4, message: 'Gas fee not set' });
+
+  // The estimate fails the same way, instead of quoting the token as free
+  await world.query({
+    callee: contract,
+    funcName: 'estimateGasFee',
+    funcArgs: [e.Str('ethereum'), e.U64(7), e.Str(TOKEN_IDENTIFIER)],
+  }).assertFail({ code: 4, message: 'Gas fee not set' });
+
+  const query = await world.query({
+    callee: contract,
+    funcName: 'estimateGasFee',
+    funcArgs: [e.Str('ethereum'), e.U64(7), e.Str('EGLD')],
+  });
+  assert(query.returnData[0] === '042e');
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'payNativeGasForContractCall',
+    value: 1_070,
+    funcArgs: [
+      deployer,
+      e.Str('polygon'),
+      e.Str('mockAddress'),
+      e.Str('payload'),
+      e.Addr(deployer.toString()),
+    ],
+  }).assertFail({ code: 4, message: 'Gas fee not set' });
+
+  let pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 2_070,
+    kvs: [
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('gas_fees', e.Str('ethereum'), e.Str('EGLD')).Value(e.Tuple(e.U(1_000), e.U(10))),
+      e.kvs.Mapper('min_gas_fee_enforced').Value(e.Bool(true)),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(2)),
+      gasPaymentKvs(1, 'EGLD', 1_000, e.Addr(deployer.toString())),
+      gasPaymentKvs(2, 'EGLD', 1_070, e.Addr(deployer.toString())),
+      paymentDestinationChainKvs(1, 'ethereum'),
+      paymentDestinationChainKvs(2, 'ethereum'),
+      gasTotalsKvs('EGLD', 2_070),
+      chainGasTotalsKvs('ethereum', 'EGLD', 2_070),
+    ],
+  });
+});
+
+test('Distribute fees', async () => {
+  await deployContract();
+
+  const receiver1 = await world.createWallet();
+  const receiver2 = await world.createWallet();
+  const receiver3 = await world.createWallet();
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'setFeeReceivers',
+    funcArgs: [
+      receiver1,
+      e.U32(10_000),
+    ],
+  }).assertFail({ code: 4, message: 'Endpoint can only be called by owner' });
+
+  // Shares need to add up to 100%
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'setFeeReceivers',
+    funcArgs: [
+      receiver1,
+      e.U32(5_000),
+      receiver2,
+      e.U32(4_999),
+    ],
+  }).assertFail({ code: 4, message: 'Invalid shares' });
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'distributeFees',
+    funcArgs: [
+      e.Str('EGLD'),
+    ],
+  }).assertFail({ code: 4, message: 'No fee receivers' });
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'setFeeReceivers',
+    funcArgs: [
+      receiver1,
+      e.U32(3_333),
+      receiver2,
+      e.U32(3_333),
+      receiver3,
+      e.U32(3_334),
+    ],
+  });
+
+  await payNativeGas(1_000);
+
+  // Escrowed express gas is not distributed
+  await payNativeExpressGas(500);
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'distributeFees',
+    funcArgs: [
+      e.Str('EGLD'),
+    ],
+  }).assertFail({ code: 4, message: 'Not collector' });
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'distributeFees',
+    funcArgs: [
+      e.Str('EGLD'),
+    ],
+  });
+
+  let pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 500,
+    kvs: [
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('fee_receivers').Value(e.List(
+        e.Tuple(receiver1, e.U32(3_333)),
+        e.Tuple(receiver2, e.U32(3_333)),
+        e.Tuple(receiver3, e.U32(3_334)),
+      )),
+      e.kvs.Mapper('last_payment_id').Value(e.U64(2)),
+      gasPaymentKvs(1, 'EGLD', 1_000, e.Addr(deployer.toString())),
+      gasPaymentKvs(2, 'EGLD', 500, e.Addr(deployer.toString()), 0, false, true),
+      paymentDestinationChainKvs(1, 'ethereum'),
+      paymentDestinationChainKvs(2, 'ethereum'),
+      gasTotalsKvs('EGLD', 1_500, 0, 1_000),
+      chainGasTotalsKvs('ethereum', 'EGLD', 1_500),
+      e.kvs.Mapper('express_escrow', e.Str('EGLD')).Value(e.U(500)),
+    ],
+  });
+
+  // Shares are rounded down, with the remainder going to the first receiver
+  assertAccount(await receiver1.getAccount(), {
+    balance: 334,
+  });
+  assertAccount(await receiver2.getAccount(), {
+    balance: 333,
+  });
+  assertAccount(await receiver3.getAccount(), {
+    balance: 333,
+  });
+});
+
+test('Add and remove collector', async () => {
+  await deployContract();
+
+  const user = await world.createWallet();
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'addCollector',
+    funcArgs: [
+      user,
+    ],
+  }).assertFail({ code: 4, message: 'Endpoint can only be called by owner' });
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'addCollector',
+    funcArgs: [
+      user,
+    ],
+  });
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'addRefunder',
+    funcArgs: [
+      deployer,
+    ],
+  });
+
+  let pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 0n,
+    kvs: [
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('account_roles', user).Value(e.U32(0b00010000)), // collector role
+      e.kvs.Mapper('account_roles', deployer).Value(e.U32(0b00001000)), // refunder role
+    ],
+  });
+
+  // Refunders can not collect fees
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'collectFees',
+    funcArgs: [
+      e.Addr(deployer.toString()),
+      e.U32(1),
+      e.Str('EGLD'),
+      e.U32(1),
+      e.U(1),
+    ],
+  }).assertFail({ code: 4, message: 'Not collector' });
+
+  // Collectors can not refund
+  await user.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'refund',
+    funcArgs: [
+      e.Str('txHash'),
+      e.U(1),
+      e.U64(1),
+      e.U(1),
+    ],
+  }).assertFail({ code: 4, message: 'Not refunder' });
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'removeCollector',
+    funcArgs: [
+      collector,
+    ],
+  });
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'removeRefunder',
     funcArgs: [
       deployer,
     ],
@@ -864,7 +2146,120 @@ test('Set gas collector', async () => {
   assertAccount(pairs, {
     balance: 0n,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(deployer),
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00001000)), // refunder role
+      e.kvs.Mapper('account_roles', user).Value(e.U32(0b00010000)), // collector role
+    ],
+  });
+});
+
+test('Propose and accept collectorship', async () => {
+  await deployContract();
+
+  const user = await world.createWallet();
+
+  await user.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'proposeCollectorship',
+    funcArgs: [
+      user,
+    ],
+  }).assertFail({ code: 4, message: 'Not collector' });
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'proposeCollectorship',
+    funcArgs: [
+      user,
+    ],
+  });
+
+  let pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 0n,
+    kvs: [
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('proposed_roles', collector, user).Value(e.U32(0b00010000)),
+    ],
+  });
+
+  await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'acceptCollectorship',
+    funcArgs: [
+      collector,
+    ],
+  }).assertFail({ code: 4, message: 'Invalid proposed roles' });
+
+  // Collector role can not be accepted as refunder role
+  await user.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'acceptRefundership',
+    funcArgs: [
+      collector,
+    ],
+  }).assertFail({ code: 4, message: 'Invalid proposed roles' });
+
+  await user.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'acceptCollectorship',
+    funcArgs: [
+      collector,
+    ],
+  });
+
+  pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 0n,
+    kvs: [
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00001000)), // refunder role
+      e.kvs.Mapper('account_roles', user).Value(e.U32(0b00010000)), // collector role
+    ],
+  });
+});
+
+test('Propose and accept refundership', async () => {
+  await deployContract();
+
+  const user = await world.createWallet();
+
+  await user.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'proposeRefundership',
+    funcArgs: [
+      user,
+    ],
+  }).assertFail({ code: 4, message: 'Not refunder' });
+
+  await collector.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'proposeRefundership',
+    funcArgs: [
+      user,
+    ],
+  });
+
+  await user.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'acceptRefundership',
+    funcArgs: [
+      collector,
+    ],
+  });
+
+  const pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 0n,
+    kvs: [
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00010000)), // collector role
+      e.kvs.Mapper('account_roles', user).Value(e.U32(0b00001000)), // refunder role
     ],
   });
 });
@@ -890,11 +2285,36 @@ test('Upgrade', async () => {
     gasLimit: 100_000_000,
   });
 
-  const pairs = await contract.getAccount();
+  let pairs = await contract.getAccount();
+  assertAccount(pairs, {
+    balance: 0n,
+    kvs: [
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00011000)),
+    ],
+  });
+
+  // Gas collector set by a previous version is migrated to the collector and refunder roles
+  await contract.setAccount({
+    ...(await contract.getAccount()),
+    kvs: [
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('gas_collector').Value(deployer),
+    ],
+  });
+
+  await deployer.upgradeContract({
+    callee: contract,
+    code: 'file:gas-service/output/gas-service.wasm',
+    codeMetadata: ['upgradeable'],
+    gasLimit: 100_000_000,
+  });
+
+  pairs = await contract.getAccount();
   assertAccount(pairs, {
     balance: 0n,
     kvs: [
-      e.kvs.Mapper('gas_collector').Value(collector),
+      e.kvs.Mapper('account_roles', collector).Value(e.U32(0b00011000)),
+      e.kvs.Mapper('account_roles', deployer).Value(e.U32(0b00011000)),
     ],
   });
 });
@@ -1,16 +1,24 @@
 import { afterEach, assert, beforeEach, describe, test } from 'vitest';
-import { assertAccount, e, LSContract, LSWallet, LSWorld } from 'xsuite';
+import { assertAccount, e, Encodable, LSContract, LSWallet, LSWorld } from 'xsuite';
 import {
   ADDRESS_ZERO,
   ALICE_PUB_KEY,
   BOB_PUB_KEY,
   CAROL_PUB_KEY,
   DOMAIN_SEPARATOR,
+  executedMessageKvs,
+  generateCancelRotateSignersSignature,
+  generateCompactProof,
   generateMessageSignature,
   generateProof,
+  generateRotateDomainSeparatorSignature,
   generateRotateSignersSignature,
   getKeccak256Hash, getMessageHash,
+  generateSecp256k1MessageSignature,
   getSignersHash,
+  SECP256K1_PUB_KEY,
+  SIGNER_KEY_TYPE_SECP256K1,
+  weightedSigner,
   MESSAGE_ID,
   OTHER_CHAIN_ADDRESS,
   OTHER_CHAIN_NAME,
@@ -64,15 +72,16 @@ const baseKvs = () => {
     e.kvs.Mapper('operator').Value(firstUser),
     e.kvs.Mapper('signer_hash_by_epoch', e.U(1)).Value(e.TopBuffer(defaultSignersHash)),
     e.kvs.Mapper('epoch_by_signer_hash', e.TopBuffer(defaultSignersHash)).Value(e.U(1)),
+    e.kvs.Mapper('signers_by_epoch', e.U(1)).Value(defaultWeightedSigners),
     e.kvs.Mapper('epoch').Value(e.U(1)),
   ];
 };
 
 const defaultWeightedSigners = e.Tuple(
   e.List(
-    e.Tuple(e.TopBuffer(ALICE_PUB_KEY), e.U(5)),
-    e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(6)),
-    e.Tuple(e.TopBuffer(CAROL_PUB_KEY), e.U(7)),
+    weightedSigner(ALICE_PUB_KEY, 5),
+    weightedSigner(BOB_PUB_KEY, 6),
+    weightedSigner(CAROL_PUB_KEY, 7),
   ),
   e.U(10),
   e.TopBuffer(getKeccak256Hash('nonce1')),
@@ -164,8 +173,8 @@ test('Upgrade', async () => {
 
   const wrongWeightedSigners = e.Tuple(
     e.List(
-      e.Tuple(e.TopBuffer(CAROL_PUB_KEY), e.U(5)),
-      e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(5)),
+      weightedSigner(CAROL_PUB_KEY, 5),
+      weightedSigner(BOB_PUB_KEY, 5),
     ),
     e.U(5),
     e.TopBuffer(getKeccak256Hash('nonce2')),
@@ -185,8 +194,8 @@ test('Upgrade', async () => {
 
   const weightedSigners = e.Tuple(
     e.List(
-      e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(5)),
-      e.Tuple(e.TopBuffer(CAROL_PUB_KEY), e.U(5)),
+      weightedSigner(BOB_PUB_KEY, 5),
+      weightedSigner(CAROL_PUB_KEY, 5),
     ),
     e.U(5),
     e.TopBuffer(getKeccak256Hash('nonce2')),
@@ -221,6 +230,7 @@ test('Upgrade', async () => {
       e.kvs.Mapper('operator').Value(deployer),
       e.kvs.Mapper('signer_hash_by_epoch', e.U(2)).Value(e.TopBuffer(signersHash)),
       e.kvs.Mapper('epoch_by_signer_hash', e.TopBuffer(signersHash)).Value(e.U(2)),
+      e.kvs.Mapper('signers_by_epoch', e.U(2)).Value(weightedSigners),
       e.kvs.Mapper('epoch').Value(e.U(2)),
     ],
   });
@@ -255,7 +265,7 @@ describe('Approve messages', () => {
     await deployContract();
 
     const invalidWeightedSigners = e.Tuple(
-      e.List(e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(5))),
+      e.List(weightedSigner(BOB_PUB_KEY, 5)),
       e.U(5),
       e.TopBuffer(getKeccak256Hash('nonce2')),
     );
@@ -424,7 +434,7 @@ describe('Approve messages', () => {
         ...baseKvs(),
 
         // Manually execute message
-        e.kvs.Mapper('messages', crossChainId).Value(e.Str('1')),
+        executedMessageKvs(crossChainId),
       ],
     });
 
@@ -452,7 +462,7 @@ describe('Approve messages', () => {
         ...baseKvs(),
 
         // Message was executed
-        e.kvs.Mapper('messages', crossChainId).Value(e.Str('1')),
+        executedMessageKvs(crossChainId),
       ],
     });
   });
@@ -497,11 +507,346 @@ describe('Approve messages', () => {
       ],
     });
   });
+
+  test('Approved and skipped counts', async () => {
+    await deployContract();
+
+    const getMessage = (messageId: string) => e.Tuple(
+      e.Str('ethereum'),
+      e.Str(messageId),
+      e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+      deployer,
+      e.TopBuffer(PAYLOAD_HASH),
+    );
+
+    const approveMessages = (messages: Encodable[]) => deployer.callContract({
+      callee: contract,
+      gasLimit: 20_000_000,
+      funcName: 'approveMessages',
+      funcArgs: [
+        e.List(...messages),
+        generateProof(
+          defaultWeightedSigners, [
+            generateMessageSignature(defaultSignersHash, e.List(...messages)),
+            generateMessageSignature(defaultSignersHash, e.List(...messages), './bob.pem'),
+            null,
+          ],
+        ),
+      ],
+    });
+
+    let result = await approveMessages([getMessage('messageId'), getMessage('otherMessageId')]);
+    assert(result.returnData[0] === '02');
+    assert(result.returnData[1] === '');
+
+    // Resubmitted batch only approves the new message
+    result = await approveMessages([getMessage('messageId'), getMessage('otherMessageId'), getMessage('thirdMessageId')]);
+    assert(result.returnData[0] === '01');
+    assert(result.returnData[1] === '02');
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('messages', e.Tuple(e.Str('ethereum'), e.Str('messageId'))).Value(
+          getMessageHash('ethereum', 'messageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', deployer),
+        ),
+        e.kvs.Mapper('messages', e.Tuple(e.Str('ethereum'), e.Str('otherMessageId'))).Value(
+          getMessageHash('ethereum', 'otherMessageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', deployer),
+        ),
+        e.kvs.Mapper('messages', e.Tuple(e.Str('ethereum'), e.Str('thirdMessageId'))).Value(
+          getMessageHash('ethereum', 'thirdMessageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', deployer),
+        ),
+      ],
+    });
+  });
+
+  // Only runs when GATEWAY_BASELINE_WASM points to a build of the previous Gateway, see the README
+  test.skipIf(!process.env.GATEWAY_BASELINE_WASM)('Gas compared to the previous Gateway', async () => {
+    await deployContract();
+
+    // The previous Gateway has 32 bytes signers without key types and 64 bytes signatures
+    const baselineWeightedSigners = e.Tuple(
+      e.List(
+        e.Tuple(e.TopBuffer(ALICE_PUB_KEY), e.U(5)),
+        e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(6)),
+        e.Tuple(e.TopBuffer(CAROL_PUB_KEY), e.U(7)),
+      ),
+      e.U(10),
+      e.TopBuffer(getKeccak256Hash('nonce1')),
+    );
+
+    const { contract: baselineContract } = await deployer.deployContract({
+      code: `file:${process.env.GATEWAY_BASELINE_WASM}`,
+      codeMetadata: ['upgradeable'],
+      gasLimit: 100_000_000,
+      codeArgs: [
+        e.U(16),
+        e.TopBuffer(DOMAIN_SEPARATOR),
+        e.U64(3600),
+        firstUser,
+        baselineWeightedSigners,
+      ],
+    });
+
+    const messages = (prefix: string, count: number) => [...Array(count).keys()].map((index) => e.Tuple(
+      e.Str('ethereum'),
+      e.Str(`${prefix}${index}`),
+      e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+      deployer,
+      e.TopBuffer(PAYLOAD_HASH),
+    ));
+
+    const approveMessages = async (callee: LSContract, batch: Encodable[], baseline: boolean) => {
+      const signatures = [
+        generateMessageSignature(defaultSignersHash, e.List(...batch)),
+        generateMessageSignature(defaultSignersHash, e.List(...batch), './bob.pem'),
+      ];
+
+      const proof = baseline
+        ? e.Tuple(
+          baselineWeightedSigners,
+          e.List(...signatures.map((signature) => e.Option(e.TopBuffer(signature))), e.Option(null)),
+        )
+        : generateProof(defaultWeightedSigners, [...signatures, null]);
+
+      const result = await deployer.callContract({
+        callee,
+        gasLimit: 100_000_000,
+        funcName: 'approveMessages',
+        funcArgs: [e.List(...batch), proof],
+      });
+
+      return { returnData: result.returnData, gasUsed: Number(result.tx.gasUsed) };
+    };
+
+    // Gas used for each message, from the difference between batches of 1 and 11 messages
+    const measure = async (callee: LSContract, baseline: boolean) => {
+      const single = messages('single', 1);
+      const batch = messages('batch', 11);
+
+      const newSingle = await approveMessages(callee, single, baseline);
+      const newBatch = await approveMessages(callee, batch, baseline);
+      const resubmittedSingle = await approveMessages(callee, single, baseline);
+      const resubmittedBatch = await approveMessages(callee, batch, baseline);
+
+      if (!baseline) {
+        assert.deepEqual(newBatch.returnData, ['0b', '']);
+        assert.deepEqual(resubmittedBatch.returnData, ['', '0b']);
+      }
+
+      return {
+        newMessage: (newBatch.gasUsed - newSingle.gasUsed) / 10,
+        resubmittedMessage: (resubmittedBatch.gasUsed - resubmittedSingle.gasUsed) / 10,
+      };
+    };
+
+    console.table({
+      previous: await measure(baselineContract, true),
+      current: await measure(contract, false),
+    });
+  });
+});
+
+describe('Source chains', () => {
+  const message = e.Tuple(
+    e.Str('ethereum'),
+    e.Str('messageId'),
+    e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+    e.Addr(ADDRESS_ZERO),
+    e.TopBuffer(PAYLOAD_HASH),
+  );
+  const otherMessage = e.Tuple(
+    e.Str('polygon'),
+    e.Str('messageId'),
+    e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+    e.Addr(ADDRESS_ZERO),
+    e.TopBuffer(PAYLOAD_HASH),
+  );
+  const secondMessage = e.Tuple(
+    e.Str('ethereum'),
+    e.Str('otherMessageId'),
+    e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+    e.Addr(ADDRESS_ZERO),
+    e.TopBuffer(PAYLOAD_HASH),
+  );
+
+  const approveMessages = (messages: Encodable[]) => deployer.callContract({
+    callee: contract,
+    gasLimit: 20_000_000,
+    funcName: 'approveMessages',
+    funcArgs: [
+      e.List(...messages),
+      generateProof(
+        defaultWeightedSigners, [
+          generateMessageSignature(defaultSignersHash, e.List(...messages)),
+          generateMessageSignature(defaultSignersHash, e.List(...messages), './bob.pem'),
+          null,
+        ],
+      ),
+    ],
+  });
+
+  const messageHash = getMessageHash('ethereum', 'messageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', e.Addr(ADDRESS_ZERO));
+  const secondMessageHash = getMessageHash('ethereum', 'otherMessageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', e.Addr(ADDRESS_ZERO));
+
+  test('Source chain allowlist', async () => {
+    await deployContract();
+
+    const otherWallet = await world.createWallet();
+
+    await otherWallet.callContract({
+      callee: contract,
+      funcName: 'setSourceChainAllowlistEnabled',
+      gasLimit: 5_000_000,
+      funcArgs: [e.Bool(true)],
+    }).assertFail({ code: 4, message: 'Invalid sender' });
+
+    await otherWallet.callContract({
+      callee: contract,
+      funcName: 'allowSourceChains',
+      gasLimit: 5_000_000,
+      funcArgs: [e.Str('ethereum')],
+    }).assertFail({ code: 4, message: 'Invalid sender' });
+
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'allowSourceChains',
+      gasLimit: 5_000_000,
+      funcArgs: [e.Str('')],
+    }).assertFail({ code: 4, message: 'Invalid source chain' });
+
+    // Operator and owner can manage the allowlist
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'setSourceChainAllowlistEnabled',
+      gasLimit: 5_000_000,
+      funcArgs: [e.Bool(true)],
+    });
+    await deployer.callContract({
+      callee: contract,
+      funcName: 'allowSourceChains',
+      gasLimit: 5_000_000,
+      funcArgs: [e.Str('ethereum')],
+    });
+
+    // Message from a source chain which is not allowed is rejected
+    await approveMessages([message, otherMessage]);
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('source_chain_allowlist_enabled').Value(e.Bool(true)),
+        e.kvs.Mapper('allowed_source_chains').UnorderedSet([e.Str('ethereum')]),
+        e.kvs.Mapper('messages', e.Tuple(e.Str('ethereum'), e.Str('messageId'))).Value(messageHash),
+      ],
+    });
+
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'disallowSourceChains',
+      gasLimit: 5_000_000,
+      funcArgs: [e.Str('ethereum')],
+    });
+
+    // All source chains are rejected if the allowlist is empty
+    await approveMessages([secondMessage]);
+
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'setSourceChainAllowlistEnabled',
+      gasLimit: 5_000_000,
+      funcArgs: [e.Bool(false)],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('allowed_source_chains').UnorderedSet([]),
+        e.kvs.Mapper('messages', e.Tuple(e.Str('ethereum'), e.Str('messageId'))).Value(messageHash),
+      ],
+    });
+  });
+
+  test('Approval rate limit', async () => {
+    await deployContract();
+
+    await deployer.callContract({
+      callee: contract,
+      funcName: 'setApprovalRateLimit',
+      gasLimit: 5_000_000,
+      funcArgs: [e.Str('ethereum'), e.U64(1), e.U64(0)],
+    }).assertFail({ code: 4, message: 'Invalid rate limit window' });
+
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'setApprovalRateLimit',
+      gasLimit: 5_000_000,
+      funcArgs: [e.Str('ethereum'), e.U64(1), e.U64(3600)],
+    });
+
+    // Only one message per window can be approved, other source chains are not limited
+    await approveMessages([message, secondMessage, otherMessage]);
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('approval_rate_limit', e.Str('ethereum')).Value(e.Tuple(e.U64(1), e.U64(3600))),
+        e.kvs.Mapper('approval_window', e.Str('ethereum')).Value(e.Tuple(e.U64(0), e.U64(1))),
+        e.kvs.Mapper('messages', e.Tuple(e.Str('ethereum'), e.Str('messageId'))).Value(messageHash),
+        e.kvs.Mapper('messages', e.Tuple(e.Str('polygon'), e.Str('messageId'))).Value(
+          getMessageHash('polygon', 'messageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', e.Addr(ADDRESS_ZERO)),
+        ),
+      ],
+    });
+
+    // Rejected message can be approved in the next window
+    await world.setCurrentBlockInfo({ timestamp: 3600 });
+
+    await approveMessages([secondMessage]);
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('approval_rate_limit', e.Str('ethereum')).Value(e.Tuple(e.U64(1), e.U64(3600))),
+        e.kvs.Mapper('approval_window', e.Str('ethereum')).Value(e.Tuple(e.U64(1), e.U64(1))),
+        e.kvs.Mapper('messages', e.Tuple(e.Str('ethereum'), e.Str('messageId'))).Value(messageHash),
+        e.kvs.Mapper('messages', e.Tuple(e.Str('ethereum'), e.Str('otherMessageId'))).Value(secondMessageHash),
+        e.kvs.Mapper('messages', e.Tuple(e.Str('polygon'), e.Str('messageId'))).Value(
+          getMessageHash('polygon', 'messageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', e.Addr(ADDRESS_ZERO)),
+        ),
+      ],
+    });
+
+    // Limit of 0 removes the rate limit
+    await deployer.callContract({
+      callee: contract,
+      funcName: 'setApprovalRateLimit',
+      gasLimit: 5_000_000,
+      funcArgs: [e.Str('ethereum'), e.U64(0), e.U64(0)],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('messages', e.Tuple(e.Str('ethereum'), e.Str('messageId'))).Value(messageHash),
+        e.kvs.Mapper('messages', e.Tuple(e.Str('ethereum'), e.Str('otherMessageId'))).Value(secondMessageHash),
+        e.kvs.Mapper('messages', e.Tuple(e.Str('polygon'), e.Str('messageId'))).Value(
+          getMessageHash('polygon', 'messageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', e.Addr(ADDRESS_ZERO)),
+        ),
+      ],
+    });
+  });
 });
 
 describe('Rotate signers', () => {
   const newWeightedSigners = e.Tuple(
-    e.List(e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(5))),
+    e.List(weightedSigner(BOB_PUB_KEY, 5)),
     e.U(5),
     e.TopBuffer(getKeccak256Hash('nonce2')),
   );
@@ -607,8 +952,8 @@ describe('Rotate signers', () => {
 
     newWeightedSignersInvalid = e.Tuple(
       e.List(
-        e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(5)),
-        e.Tuple(e.TopBuffer(ALICE_PUB_KEY), e.U(5)),
+        weightedSigner(BOB_PUB_KEY, 5),
+        weightedSigner(ALICE_PUB_KEY, 5),
       ),
       e.U(0),
       e.TopBuffer(getKeccak256Hash('nonce2')),
@@ -633,8 +978,8 @@ describe('Rotate signers', () => {
 
     newWeightedSignersInvalid = e.Tuple(
       e.List(
-        e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(5)),
-        e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(5)),
+        weightedSigner(BOB_PUB_KEY, 5),
+        weightedSigner(BOB_PUB_KEY, 5),
       ),
       e.U(0),
       e.TopBuffer(getKeccak256Hash('nonce2')),
@@ -659,7 +1004,58 @@ describe('Rotate signers', () => {
 
     newWeightedSignersInvalid = e.Tuple(
       e.List(
-        e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(0)),
+        weightedSigner(BOB_PUB_KEY, 5, SIGNER_KEY_TYPE_SECP256K1),
+      ),
+      e.U(5),
+      e.TopBuffer(getKeccak256Hash('nonce2')),
+    );
+
+    // Invalid key length for key type
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'rotateSigners',
+      funcArgs: [
+        newWeightedSignersInvalid,
+        generateProof(
+          defaultWeightedSigners, [
+            generateRotateSignersSignature(defaultSignersHash, newWeightedSignersInvalid),
+            generateRotateSignersSignature(defaultSignersHash, newWeightedSignersInvalid, './bob.pem'),
+            null,
+          ],
+        ),
+      ],
+    }).assertFail({ code: 4, message: 'Invalid signers' });
+
+    newWeightedSignersInvalid = e.Tuple(
+      e.List(
+        weightedSigner(SECP256K1_PUB_KEY, 5, SIGNER_KEY_TYPE_SECP256K1),
+        weightedSigner(BOB_PUB_KEY, 5),
+      ),
+      e.U(5),
+      e.TopBuffer(getKeccak256Hash('nonce2')),
+    );
+
+    // Invalid order, ed25519 signers need to be before secp256k1 signers
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'rotateSigners',
+      funcArgs: [
+        newWeightedSignersInvalid,
+        generateProof(
+          defaultWeightedSigners, [
+            generateRotateSignersSignature(defaultSignersHash, newWeightedSignersInvalid),
+            generateRotateSignersSignature(defaultSignersHash, newWeightedSignersInvalid, './bob.pem'),
+            null,
+          ],
+        ),
+      ],
+    }).assertFail({ code: 4, message: 'Invalid signers' });
+
+    newWeightedSignersInvalid = e.Tuple(
+      e.List(
+        weightedSigner(BOB_PUB_KEY, 0),
       ),
       e.U(0),
       e.TopBuffer(getKeccak256Hash('nonce2')),
@@ -683,7 +1079,7 @@ describe('Rotate signers', () => {
 
     newWeightedSignersInvalid = e.Tuple(
       e.List(
-        e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(1)),
+        weightedSigner(BOB_PUB_KEY, 1),
       ),
       e.U(0),
       e.TopBuffer(getKeccak256Hash('nonce2')),
@@ -708,7 +1104,7 @@ describe('Rotate signers', () => {
 
     newWeightedSignersInvalid = e.Tuple(
       e.List(
-        e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(1)),
+        weightedSigner(BOB_PUB_KEY, 1),
       ),
       e.U(2),
       e.TopBuffer(getKeccak256Hash('nonce2')),
@@ -868,6 +1264,7 @@ describe('Rotate signers', () => {
         e.kvs.Mapper('operator').Value(firstUser),
         e.kvs.Mapper('signer_hash_by_epoch', e.U(2)).Value(e.TopBuffer(signersHash)),
         e.kvs.Mapper('epoch_by_signer_hash', e.TopBuffer(signersHash)).Value(e.U(2)),
+        e.kvs.Mapper('signers_by_epoch', e.U(2)).Value(newWeightedSigners),
         e.kvs.Mapper('epoch').Value(e.U(2)),
         e.kvs.Mapper('last_rotation_timestamp').Value(e.U64(3600)),
       ],
@@ -950,7 +1347,7 @@ describe('Rotate signers', () => {
     }).assertFail({ code: 4, message: 'Duplicate signers' });
 
     const new2WeightedSigners = e.Tuple(
-      e.List(e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(5))),
+      e.List(weightedSigner(BOB_PUB_KEY, 5)),
       e.U(5),
       e.TopBuffer(getKeccak256Hash('nonce3')),
     );
@@ -992,153 +1389,1411 @@ describe('Rotate signers', () => {
 
         e.kvs.Mapper('signer_hash_by_epoch', e.U(3)).Value(e.TopBuffer(newSignersHash)),
         e.kvs.Mapper('epoch_by_signer_hash', e.TopBuffer(newSignersHash)).Value(e.U(3)),
+        e.kvs.Mapper('signers_by_epoch', e.U(3)).Value(new2WeightedSigners),
       ],
     });
   });
-});
-
-test('Call contract', async () => {
-  await deployContract();
-
-  await deployer.callContract({
-    callee: contract,
-    gasLimit: 10_000_000,
-    funcName: 'callContract',
-    funcArgs: [
-      e.Str('ethereum'),
-      e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
-      e.Str('payload'),
-    ],
-  });
-
-  // This only emits an event, and there is no way to test those currently...
-  assertAccount(await contract.getAccount(), {
-    balance: 0,
-    kvs: baseKvs(),
-  });
-});
 
-describe('Validate message', () => {
-  test('Validate message invalid', async () => {
+  test('Rotate signers mixed key types', async () => {
     await deployContract();
 
-    const result = await deployer.callContract({
-      callee: contract,
-      gasLimit: 10_000_000,
-      funcName: 'validateMessage',
-      funcArgs: [
-        e.Str('ethereum'),
-        e.Str('messageId'),
-        e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
-        e.TopBuffer(PAYLOAD_HASH),
-      ],
+    await world.setCurrentBlockInfo({
+      timestamp: 3600,
     });
-    assert(result.returnData[0] === '');
 
-    assertAccount(await contract.getAccount(), {
-      balance: 0,
-      kvs: baseKvs(),
-    });
+    const mixedWeightedSigners = e.Tuple(
+      e.List(
+        weightedSigner(ALICE_PUB_KEY, 5),
+        weightedSigner(SECP256K1_PUB_KEY, 5, SIGNER_KEY_TYPE_SECP256K1),
+      ),
+      e.U(10),
+      e.TopBuffer(getKeccak256Hash('nonce2')),
+    );
+    const mixedSignersHash = getSignersHash(
+      [
+        { signer: ALICE_PUB_KEY, weight: 5 },
+        { signer: SECP256K1_PUB_KEY, weight: 5, keyType: SIGNER_KEY_TYPE_SECP256K1 },
+      ],
+      10,
+      getKeccak256Hash('nonce2'),
+    );
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'rotateSigners',
+      funcArgs: [
+        mixedWeightedSigners,
+        generateProof(
+          defaultWeightedSigners, [
+            generateRotateSignersSignature(defaultSignersHash, mixedWeightedSigners),
+            generateRotateSignersSignature(defaultSignersHash, mixedWeightedSigners, './bob.pem'),
+            null,
+          ],
+        ),
+      ],
+    });
+
+    const message = e.Tuple(
+      e.Str('ethereum'),
+      e.Str('messageId'),
+      e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+      deployer,
+      e.TopBuffer(PAYLOAD_HASH),
+    );
+    const messageHash = getMessageHash('ethereum', 'messageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', deployer);
+
+    const crossChainId = e.Tuple(e.Str('ethereum'), e.Str('messageId'));
+
+    // Both the ed25519 and the secp256k1 signatures are needed to reach the threshold
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 20_000_000,
+      funcName: 'approveMessages',
+      funcArgs: [
+        e.List(message),
+        generateProof(
+          mixedWeightedSigners, [
+            generateMessageSignature(mixedSignersHash, e.List(message)),
+            null,
+          ],
+        ),
+      ],
+    }).assertFail({ code: 4, message: 'Low signatures weight' });
+
+    // Secp256k1 signature for other data
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 20_000_000,
+      funcName: 'approveMessages',
+      funcArgs: [
+        e.List(message),
+        generateProof(
+          mixedWeightedSigners, [
+            generateMessageSignature(mixedSignersHash, e.List(message)),
+            generateSecp256k1MessageSignature(mixedSignersHash, e.List()),
+          ],
+        ),
+      ],
+    }).assertFail({ code: 4, message: 'Invalid signature' });
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 20_000_000,
+      funcName: 'approveMessages',
+      funcArgs: [
+        e.List(message),
+        generateProof(
+          mixedWeightedSigners, [
+            generateMessageSignature(mixedSignersHash, e.List(message)),
+            generateSecp256k1MessageSignature(mixedSignersHash, e.List(message)),
+          ],
+        ),
+      ],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      balance: 0n,
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('operator').Value(firstUser),
+        e.kvs.Mapper('signer_hash_by_epoch', e.U(2)).Value(e.TopBuffer(mixedSignersHash)),
+        e.kvs.Mapper('epoch_by_signer_hash', e.TopBuffer(mixedSignersHash)).Value(e.U(2)),
+        e.kvs.Mapper('signers_by_epoch', e.U(2)).Value(mixedWeightedSigners),
+        e.kvs.Mapper('epoch').Value(e.U(2)),
+        e.kvs.Mapper('last_rotation_timestamp').Value(e.U64(3600)),
+
+        // Message was approved
+        e.kvs.Mapper('messages', crossChainId).Value(messageHash),
+      ],
+    });
+  });
+});
+
+describe('Scheduled rotation', () => {
+  const newWeightedSigners = e.Tuple(
+    e.List(weightedSigner(BOB_PUB_KEY, 5)),
+    e.U(5),
+    e.TopBuffer(getKeccak256Hash('nonce2')),
+  );
+  const newSignersHash = getSignersHash(
+    [
+      { signer: BOB_PUB_KEY, weight: 5 },
+    ],
+    5,
+    getKeccak256Hash('nonce2'),
+  );
+
+  const rotateSignersProof = () => generateProof(
+    defaultWeightedSigners, [
+      generateRotateSignersSignature(defaultSignersHash, newWeightedSigners),
+      generateRotateSignersSignature(defaultSignersHash, newWeightedSigners, './bob.pem'),
+      null,
+    ],
+  );
+
+  test('Schedule and execute rotation', async () => {
+    await deployContract();
+
+    // Only operator can schedule a rotation
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'scheduleRotateSigners',
+      funcArgs: [newWeightedSigners, rotateSignersProof()],
+    }).assertFail({ code: 4, message: 'Invalid sender' });
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'executeScheduledRotation',
+      funcArgs: [],
+    }).assertFail({ code: 4, message: 'No scheduled rotation' });
+
+    await firstUser.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'scheduleRotateSigners',
+      funcArgs: [newWeightedSigners, rotateSignersProof()],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('scheduled_rotation').Value(e.Tuple(newWeightedSigners, e.U64(3600))),
+      ],
+    });
+
+    await firstUser.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'scheduleRotateSigners',
+      funcArgs: [newWeightedSigners, rotateSignersProof()],
+    }).assertFail({ code: 4, message: 'Rotation already scheduled' });
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'executeScheduledRotation',
+      funcArgs: [],
+    }).assertFail({ code: 4, message: 'Rotation not ready' });
+
+    await world.setCurrentBlockInfo({
+      timestamp: 3600,
+    });
+
+    // Anyone can execute the rotation after the eta
+    const otherWallet = await world.createWallet();
+
+    await otherWallet.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'executeScheduledRotation',
+      funcArgs: [],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('epoch').Value(e.U(2)),
+        e.kvs.Mapper('last_rotation_timestamp').Value(e.U64(3600)),
+        e.kvs.Mapper('signer_hash_by_epoch', e.U(2)).Value(e.TopBuffer(newSignersHash)),
+        e.kvs.Mapper('epoch_by_signer_hash', e.TopBuffer(newSignersHash)).Value(e.U(2)),
+        e.kvs.Mapper('signers_by_epoch', e.U(2)).Value(newWeightedSigners),
+      ],
+    });
+  });
+
+  test('Cancel rotation by governance', async () => {
+    await deployContract();
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'cancelScheduledRotation',
+      funcArgs: [],
+    }).assertFail({ code: 4, message: 'No scheduled rotation' });
+
+    await firstUser.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'scheduleRotateSigners',
+      funcArgs: [newWeightedSigners, rotateSignersProof()],
+    });
+
+    // Operator can not cancel the rotation
+    await firstUser.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'cancelScheduledRotation',
+      funcArgs: [],
+    }).assertFail({ code: 4, message: 'Invalid sender' });
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'cancelScheduledRotation',
+      funcArgs: [],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: baseKvs(),
+    });
+  });
+
+  test('Cancel rotation by signers', async () => {
+    await deployContract();
+
+    await firstUser.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'scheduleRotateSigners',
+      funcArgs: [newWeightedSigners, rotateSignersProof()],
+    });
+
+    // Proof for rotating signers can not be used to cancel the rotation
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'cancelScheduledRotationWithProof',
+      funcArgs: [rotateSignersProof()],
+    }).assertFail({ code: 10, message: 'invalid signature' });
+
+    // Proof without the eta can not be used to cancel the rotation
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'cancelScheduledRotationWithProof',
+      funcArgs: [
+        generateProof(
+          defaultWeightedSigners, [
+            generateCancelRotateSignersSignature(defaultSignersHash, newWeightedSigners),
+            generateCancelRotateSignersSignature(defaultSignersHash, newWeightedSigners, './bob.pem'),
+            null,
+          ],
+        ),
+      ],
+    }).assertFail({ code: 10, message: 'invalid signature' });
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'cancelScheduledRotationWithProof',
+      funcArgs: [
+        generateProof(
+          defaultWeightedSigners, [
+            generateCancelRotateSignersSignature(defaultSignersHash, e.Tuple(newWeightedSigners, e.U64(3600))),
+            generateCancelRotateSignersSignature(defaultSignersHash, e.Tuple(newWeightedSigners, e.U64(3600)), './bob.pem'),
+            null,
+          ],
+        ),
+      ],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: baseKvs(),
+    });
+  });
+});
+
+describe('Domain separator', () => {
+  const newDomainSeparator = getKeccak256Hash('newDomainSeparator');
+
+  const approveMessage = (messageId: string, signaturesDomainSeparator: string, domainSeparator: Encodable[] = []) => {
+    const message = e.Tuple(
+      e.Str('ethereum'),
+      e.Str(messageId),
+      e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+      deployer,
+      e.TopBuffer(PAYLOAD_HASH),
+    );
+
+    return deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'approveMessages',
+      funcArgs: [
+        e.List(message),
+        generateProof(
+          defaultWeightedSigners, [
+            generateMessageSignature(defaultSignersHash, e.List(message), './alice.pem', signaturesDomainSeparator),
+            generateMessageSignature(defaultSignersHash, e.List(message), './bob.pem', signaturesDomainSeparator),
+            null,
+          ],
+        ),
+        ...domainSeparator,
+      ],
+    });
+  };
+
+  const rotationProof = (rotation: Encodable) => generateProof(
+    defaultWeightedSigners, [
+      generateRotateDomainSeparatorSignature(defaultSignersHash, rotation),
+      generateRotateDomainSeparatorSignature(defaultSignersHash, rotation, './bob.pem'),
+      null,
+    ],
+  );
+
+  test('Rotate domain separator', async () => {
+    await deployContract();
+
+    const rotation = e.Tuple(e.TopBuffer(newDomainSeparator), e.U64(3600));
+
+    // Signatures for another command type are not accepted
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'rotateDomainSeparator',
+      funcArgs: [
+        rotation,
+        generateProof(
+          defaultWeightedSigners, [
+            generateRotateSignersSignature(defaultSignersHash, rotation),
+            generateRotateSignersSignature(defaultSignersHash, rotation, './bob.pem'),
+            null,
+          ],
+        ),
+      ],
+    }).assertFail({ code: 10, message: 'invalid signature' });
+
+    const sameRotation = e.Tuple(e.TopBuffer(DOMAIN_SEPARATOR), e.U64(3600));
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'rotateDomainSeparator',
+      funcArgs: [sameRotation, rotationProof(sameRotation)],
+    }).assertFail({ code: 4, message: 'Invalid domain separator' });
+
+    // Grace period is capped to 30 days
+    const longRotation = e.Tuple(e.TopBuffer(newDomainSeparator), e.U64(30 * 24 * 3600 + 1));
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'rotateDomainSeparator',
+      funcArgs: [longRotation, rotationProof(longRotation)],
+    }).assertFail({ code: 4, message: 'Invalid grace period' });
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'rotateDomainSeparator',
+      funcArgs: [rotation, rotationProof(rotation)],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('domain_separator').Value(e.TopBuffer(newDomainSeparator)),
+        e.kvs.Mapper('previous_domain_separator').Value(e.TopBuffer(DOMAIN_SEPARATOR)),
+        e.kvs.Mapper('previous_domain_separator_expiry').Value(e.U64(3600)),
+      ],
+    });
+
+    // New domain separator is used by default
+    await approveMessage('messageId', newDomainSeparator);
+
+    await approveMessage('otherMessageId', DOMAIN_SEPARATOR)
+      .assertFail({ code: 10, message: 'invalid signature' });
+
+    await approveMessage('otherMessageId', DOMAIN_SEPARATOR, [e.TopBuffer(getKeccak256Hash('other'))])
+      .assertFail({ code: 4, message: 'Invalid domain separator' });
+
+    // Previous domain separator is accepted during the grace period if provided
+    await approveMessage('otherMessageId', DOMAIN_SEPARATOR, [e.TopBuffer(DOMAIN_SEPARATOR)]);
+
+    await world.setCurrentBlockInfo({
+      timestamp: 3600,
+    });
+
+    await approveMessage('thirdMessageId', DOMAIN_SEPARATOR, [e.TopBuffer(DOMAIN_SEPARATOR)])
+      .assertFail({ code: 4, message: 'Invalid domain separator' });
+
+    // Current domain separator can also be provided
+    await approveMessage('thirdMessageId', newDomainSeparator, [e.TopBuffer(newDomainSeparator)]);
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('domain_separator').Value(e.TopBuffer(newDomainSeparator)),
+        e.kvs.Mapper('previous_domain_separator').Value(e.TopBuffer(DOMAIN_SEPARATOR)),
+        e.kvs.Mapper('previous_domain_separator_expiry').Value(e.U64(3600)),
+        e.kvs.Mapper('messages', e.Tuple(e.Str('ethereum'), e.Str('messageId'))).Value(
+          getMessageHash('ethereum', 'messageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', deployer),
+        ),
+        e.kvs.Mapper('messages', e.Tuple(e.Str('ethereum'), e.Str('otherMessageId'))).Value(
+          getMessageHash('ethereum', 'otherMessageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', deployer),
+        ),
+        e.kvs.Mapper('messages', e.Tuple(e.Str('ethereum'), e.Str('thirdMessageId'))).Value(
+          getMessageHash('ethereum', 'thirdMessageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', deployer),
+        ),
+      ],
+    });
+  });
+
+  test('Rotate domain separator without grace period', async () => {
+    await deployContract();
+
+    const rotation = e.Tuple(e.TopBuffer(newDomainSeparator), e.U64(0));
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'rotateDomainSeparator',
+      funcArgs: [rotation, rotationProof(rotation)],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('domain_separator').Value(e.TopBuffer(newDomainSeparator)),
+      ],
+    });
+
+    await approveMessage('messageId', DOMAIN_SEPARATOR, [e.TopBuffer(DOMAIN_SEPARATOR)])
+      .assertFail({ code: 4, message: 'Invalid domain separator' });
+  });
+});
+
+describe('Compact proofs', () => {
+  const message = e.Tuple(
+    e.Str('ethereum'),
+    e.Str('messageId'),
+    e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+    e.Addr(ADDRESS_ZERO),
+    e.TopBuffer(PAYLOAD_HASH),
+  );
+
+  test('Approve messages compact errors', async () => {
+    await deployContract();
+
+    const aliceSignature = generateMessageSignature(defaultSignersHash, e.List(message));
+    const bobSignature = generateMessageSignature(defaultSignersHash, e.List(message), './bob.pem');
+
+    // Signers for epoch are not stored
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'approveMessagesCompact',
+      funcArgs: [
+        e.List(message),
+        generateCompactProof(2, [aliceSignature, bobSignature, null]),
+      ],
+    }).assertFail({ code: 4, message: 'Invalid signers' });
+
+    // Bitmap has invalid length
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'approveMessagesCompact',
+      funcArgs: [
+        e.List(message),
+        e.Tuple(e.U(1), e.Buffer('0300'), e.List(e.Buffer(aliceSignature), e.Buffer(bobSignature))),
+      ],
+    }).assertFail({ code: 4, message: 'Invalid signers bitmap' });
+
+    // Bitmap has bits set for signers which do not exist
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'approveMessagesCompact',
+      funcArgs: [
+        e.List(message),
+        e.Tuple(e.U(1), e.Buffer('0b'), e.List(e.Buffer(aliceSignature), e.Buffer(bobSignature))),
+      ],
+    }).assertFail({ code: 4, message: 'Invalid signers bitmap' });
+
+    // Less signatures than bits set
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'approveMessagesCompact',
+      funcArgs: [
+        e.List(message),
+        e.Tuple(e.U(1), e.Buffer('03'), e.List(e.Buffer(aliceSignature))),
+      ],
+    }).assertFail({ code: 4, message: 'Invalid signatures' });
+
+    // More signatures than bits set
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'approveMessagesCompact',
+      funcArgs: [
+        e.List(message),
+        e.Tuple(e.U(1), e.Buffer('01'), e.List(e.Buffer(aliceSignature), e.Buffer(bobSignature))),
+      ],
+    }).assertFail({ code: 4, message: 'Invalid signatures' });
+
+    // Partial signatures not enough
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'approveMessagesCompact',
+      funcArgs: [
+        e.List(message),
+        generateCompactProof(1, [aliceSignature, null, null]),
+      ],
+    }).assertFail({ code: 4, message: 'Low signatures weight' });
+
+    // Signature of bob used for alice
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'approveMessagesCompact',
+      funcArgs: [
+        e.List(message),
+        generateCompactProof(1, [bobSignature, aliceSignature, null]),
+      ],
+    }).assertFail({ code: 10, message: 'invalid signature' });
+  });
+
+  test('Approve messages compact', async () => {
+    await deployContract();
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'approveMessagesCompact',
+      funcArgs: [
+        e.List(message),
+        generateCompactProof(1, [
+          generateMessageSignature(defaultSignersHash, e.List(message)),
+          null,
+          generateMessageSignature(defaultSignersHash, e.List(message), './carol.pem'),
+        ]),
+      ],
+    });
+
+    const messageHash = getMessageHash(
+      'ethereum',
+      'messageId',
+      '0x4976da71bF84D750b5451B053051158EC0A4E876',
+      e.Addr(ADDRESS_ZERO),
+    );
+    const crossChainId = e.Tuple(e.Str('ethereum'), e.Str('messageId'));
+
+    assertAccount(await contract.getAccount(), {
+      balance: 0n,
+      kvs: [
+        ...baseKvs(),
+
+        // Message was approved
+        e.kvs.Mapper('messages', crossChainId).Value(messageHash),
+      ],
+    });
+  });
+
+  test('Rotate signers compact', async () => {
+    await deployContract();
+
+    await world.setCurrentBlockInfo({
+      timestamp: 3600,
+    });
+
+    const newWeightedSigners = e.Tuple(
+      e.List(weightedSigner(BOB_PUB_KEY, 5)),
+      e.U(5),
+      e.TopBuffer(getKeccak256Hash('nonce2')),
+    );
+    const newSignersHash = getSignersHash(
+      [
+        { signer: BOB_PUB_KEY, weight: 5 },
+      ],
+      5,
+      getKeccak256Hash('nonce2'),
+    );
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'rotateSignersCompact',
+      funcArgs: [
+        newWeightedSigners,
+        generateCompactProof(1, [
+          generateRotateSignersSignature(defaultSignersHash, newWeightedSigners),
+          generateRotateSignersSignature(defaultSignersHash, newWeightedSigners, './bob.pem'),
+          null,
+        ]),
+      ],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      balance: 0n,
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('signer_hash_by_epoch', e.U(2)).Value(e.TopBuffer(newSignersHash)),
+        e.kvs.Mapper('epoch_by_signer_hash', e.TopBuffer(newSignersHash)).Value(e.U(2)),
+        e.kvs.Mapper('signers_by_epoch', e.U(2)).Value(newWeightedSigners),
+        e.kvs.Mapper('epoch').Value(e.U(2)),
+        e.kvs.Mapper('last_rotation_timestamp').Value(e.U64(3600)),
+      ],
+    });
+
+    // Signers of epoch 1 are no longer the latest signers
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'rotateSignersCompact',
+      funcArgs: [
+        defaultWeightedSigners,
+        generateCompactProof(1, [
+          generateRotateSignersSignature(defaultSignersHash, defaultWeightedSigners),
+          generateRotateSignersSignature(defaultSignersHash, defaultWeightedSigners, './bob.pem'),
+          null,
+        ]),
+      ],
+    }).assertFail({ code: 4, message: 'Not latest signers' });
+  });
+});
+
+test('Call contract', async () => {
+  await deployContract();
+
+  const getOutboundMessageId = (nonce: number) => getKeccak256Hash(
+    Buffer.concat([
+      e.U64(nonce).toNestU8A(),
+      deployer.toTopU8A(),
+      Buffer.from(getKeccak256Hash('payload'), 'hex'),
+    ]),
+  );
+
+  let result = await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'callContract',
+    funcArgs: [
+      e.Str('ethereum'),
+      e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+      e.Str('payload'),
+    ],
+  });
+  assert(result.returnData[0] === getOutboundMessageId(0));
+
+  assertAccount(await contract.getAccount(), {
+    balance: 0,
+    kvs: [
+      ...baseKvs(),
+
+      e.kvs.Mapper('outbound_nonce').Value(e.U64(1)),
+    ],
+  });
+
+  // Same call has a different message id
+  result = await deployer.callContract({
+    callee: contract,
+    gasLimit: 10_000_000,
+    funcName: 'callContract',
+    funcArgs: [
+      e.Str('ethereum'),
+      e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+      e.Str('payload'),
+    ],
+  });
+  assert(result.returnData[0] === getOutboundMessageId(1));
+
+  assertAccount(await contract.getAccount(), {
+    balance: 0,
+    kvs: [
+      ...baseKvs(),
+
+      e.kvs.Mapper('outbound_nonce').Value(e.U64(2)),
+    ],
+  });
+});
+
+describe('Validate message', () => {
+  test('Validate message invalid', async () => {
+    await deployContract();
+
+    const result = await deployer.callContract({
+      callee: contract,
+      gasLimit: 10_000_000,
+      funcName: 'validateMessage',
+      funcArgs: [
+        e.Str('ethereum'),
+        e.Str('messageId'),
+        e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+        e.TopBuffer(PAYLOAD_HASH),
+      ],
+    });
+    assert(result.returnData[0] === '');
+
+    assertAccount(await contract.getAccount(), {
+      balance: 0,
+      kvs: baseKvs(),
+    });
+  });
+
+  test('Validate message valid', async () => {
+    await deployContract();
+
+    const messageHash = getMessageHash('ethereum', 'messageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', deployer);
+
+    const crossChainId = e.Tuple(e.Str('ethereum'), e.Str('messageId'));
+
+    await contract.setAccount({
+      ...await contract.getAccount(),
+      codeMetadata: ['payable'],
+      kvs: [
+        ...baseKvs(),
+
+        // Manually approve message
+        e.kvs.Mapper('messages', crossChainId).Value(messageHash),
+      ],
+    });
+
+    const result = await deployer.callContract({
+      callee: contract,
+      gasLimit: 10_000_000,
+      funcName: 'validateMessage',
+      funcArgs: [
+        e.Str('ethereum'),
+        e.Str('messageId'),
+        e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+        e.TopBuffer(PAYLOAD_HASH),
+      ],
+    });
+    assert(result.returnData[0] === '01');
+
+    assertAccount(await contract.getAccount(), {
+      balance: 0,
+      kvs: [
+        ...baseKvs(),
+
+        // Message was executed
+        executedMessageKvs(crossChainId),
+      ],
+    });
+  });
+});
+
+test('Prune executed messages', async () => {
+  await deployContract();
+
+  const crossChainId = e.Tuple(e.Str('ethereum'), e.Str('messageId'));
+  const otherCrossChainId = e.Tuple(e.Str('ethereum'), e.Str('otherMessageId'));
+  const messageHash = getMessageHash('ethereum', 'otherMessageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', deployer);
+
+  // Mock one message executed before the upgrade and one approved
+  await contract.setAccount({
+    ...await contract.getAccount(),
+    kvs: [
+      ...baseKvs(),
+
+      e.kvs.Mapper('messages', crossChainId).Value(e.Str('1')),
+      e.kvs.Mapper('messages', otherCrossChainId).Value(messageHash),
+    ],
+  });
+
+  let result = await world.query({
+    callee: contract,
+    funcName: 'isMessageExecuted',
+    funcArgs: [
+      e.Str('ethereum'),
+      e.Str('messageId'),
+    ],
+  });
+  assert(result.returnData[0] === '01');
+
+  // Anyone can prune executed messages, approved and non existent messages are not changed
+  await firstUser.callContract({
+    callee: contract,
+    funcName: 'pruneExecutedMessages',
+    gasLimit: 10_000_000,
+    funcArgs: [
+      crossChainId,
+      otherCrossChainId,
+      e.Tuple(e.Str('ethereum'), e.Str('nonExistentMessageId')),
+    ],
+  });
+
+  assertAccount(await contract.getAccount(), {
+    kvs: [
+      ...baseKvs(),
+
+      executedMessageKvs(crossChainId),
+      e.kvs.Mapper('messages', otherCrossChainId).Value(messageHash),
+    ],
+  });
+
+  result = await world.query({
+    callee: contract,
+    funcName: 'isMessageExecuted',
+    funcArgs: [
+      e.Str('ethereum'),
+      e.Str('messageId'),
+    ],
+  });
+  assert(result.returnData[0] === '01');
+});
+
+describe('Operator', () => {
+  test('Transfer operatorship', async () => {
+    await deployContract();
+
+    const otherWallet = await world.createWallet();
+
+    await otherWallet.callContract({
+      callee: contract,
+      funcName: 'transferOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [
+        otherWallet,
+      ],
+    }).assertFail({ code: 4, message: 'Endpoint can only be called by owner' });
+
+    await deployer.callContract({
+      callee: contract,
+      funcName: 'transferOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [
+        e.Addr(ADDRESS_ZERO),
+      ],
+    }).assertFail({ code: 4, message: 'Invalid operator' });
+
+    // Deployer can change operatorship
+    await deployer.callContract({
+      callee: contract,
+      funcName: 'transferOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [
+        otherWallet,
+      ],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('operator').Value(otherWallet),
+      ],
+    });
+
+    // Operator needs to go through a proposal
+    await otherWallet.callContract({
+      callee: contract,
+      funcName: 'transferOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [
+        firstUser,
+      ],
+    }).assertFail({ code: 4, message: 'Endpoint can only be called by owner' });
+
+    await deployer.callContract({
+      callee: contract,
+      funcName: 'transferOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [
+        firstUser,
+      ],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: baseKvs(),
+    });
+  });
+
+  test('Propose accept operatorship', async () => {
+    await deployContract();
+
+    const otherWallet = await world.createWallet();
+
+    await otherWallet.callContract({
+      callee: contract,
+      funcName: 'proposeOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [
+        otherWallet,
+      ],
+    }).assertFail({ code: 4, message: 'Invalid sender' });
+
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'proposeOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [
+        e.Addr(ADDRESS_ZERO),
+      ],
+    }).assertFail({ code: 4, message: 'Invalid operator' });
+
+    await otherWallet.callContract({
+      callee: contract,
+      funcName: 'acceptOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [],
+    }).assertFail({ code: 4, message: 'Invalid sender' });
+
+    // Operator can propose operatorship
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'proposeOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [
+        otherWallet,
+      ],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('pending_operator').Value(otherWallet),
+      ],
+    });
+
+    const result = await world.query({
+      callee: contract,
+      funcName: 'pendingOperator',
+      funcArgs: [],
+    });
+    assert(result.returnData[0] === e.Addr(otherWallet).toTopHex());
+
+    await deployer.callContract({
+      callee: contract,
+      funcName: 'acceptOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [],
+    }).assertFail({ code: 4, message: 'Invalid sender' });
+
+    await otherWallet.callContract({
+      callee: contract,
+      funcName: 'acceptOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('operator').Value(otherWallet),
+      ],
+    });
+
+    // Proposal can not be accepted twice
+    await otherWallet.callContract({
+      callee: contract,
+      funcName: 'acceptOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [],
+    }).assertFail({ code: 4, message: 'Invalid sender' });
   });
 
-  test('Validate message valid', async () => {
+  test('Cancel operatorship proposal', async () => {
     await deployContract();
 
-    const messageHash = getMessageHash('ethereum', 'messageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', deployer);
+    const otherWallet = await world.createWallet();
 
-    const crossChainId = e.Tuple(e.Str('ethereum'), e.Str('messageId'));
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'cancelOperatorshipProposal',
+      gasLimit: 5_000_000,
+      funcArgs: [],
+    }).assertFail({ code: 4, message: 'No pending operator' });
+
+    // Deployer can also propose operatorship
+    await deployer.callContract({
+      callee: contract,
+      funcName: 'proposeOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [
+        otherWallet,
+      ],
+    });
+
+    await otherWallet.callContract({
+      callee: contract,
+      funcName: 'cancelOperatorshipProposal',
+      gasLimit: 5_000_000,
+      funcArgs: [],
+    }).assertFail({ code: 4, message: 'Invalid sender' });
+
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'cancelOperatorshipProposal',
+      gasLimit: 5_000_000,
+      funcArgs: [],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: baseKvs(),
+    });
+
+    await otherWallet.callContract({
+      callee: contract,
+      funcName: 'acceptOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [],
+    }).assertFail({ code: 4, message: 'Invalid sender' });
+
+    // Transferring operatorship directly clears the pending proposal
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'proposeOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [
+        otherWallet,
+      ],
+    });
+
+    await deployer.callContract({
+      callee: contract,
+      funcName: 'transferOperatorship',
+      gasLimit: 5_000_000,
+      funcArgs: [
+        firstUser,
+      ],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: baseKvs(),
+    });
+  });
+});
+
+describe('Revoke signers', () => {
+  test('Revoke signers', async () => {
+    await deployContract();
+
+    const otherWallet = await world.createWallet();
+
+    await otherWallet.callContract({
+      callee: contract,
+      funcName: 'revokeSigners',
+      gasLimit: 5_000_000,
+      funcArgs: [e.U(1)],
+    }).assertFail({ code: 4, message: 'Invalid sender' });
+
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'revokeSigners',
+      gasLimit: 5_000_000,
+      funcArgs: [e.U(2)],
+    }).assertFail({ code: 4, message: 'Invalid epoch' });
+
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'revokeSignersByHash',
+      gasLimit: 5_000_000,
+      funcArgs: [e.TopBuffer(getKeccak256Hash('other'))],
+    }).assertFail({ code: 4, message: 'Invalid signers hash' });
+
+    const signersHash = getSignersHash(
+      [
+        { signer: BOB_PUB_KEY, weight: 5 },
+      ],
+      5,
+      getKeccak256Hash('nonce2'),
+    );
 
+    // Mock new signers exist, so default signers are inside the retention window
     await contract.setAccount({
       ...await contract.getAccount(),
-      codeMetadata: ['payable'],
       kvs: [
         ...baseKvs(),
 
-        // Manually approve message
-        e.kvs.Mapper('messages', crossChainId).Value(messageHash),
+        e.kvs.Mapper('signer_hash_by_epoch', e.U(2)).Value(e.TopBuffer(signersHash)),
+        e.kvs.Mapper('epoch_by_signer_hash', e.TopBuffer(signersHash)).Value(e.U(2)),
+        e.kvs.Mapper('epoch').Value(e.U(2)),
       ],
     });
 
-    const result = await deployer.callContract({
+    // Operator can revoke signers
+    await firstUser.callContract({
       callee: contract,
-      gasLimit: 10_000_000,
-      funcName: 'validateMessage',
+      funcName: 'revokeSignersByHash',
+      gasLimit: 5_000_000,
+      funcArgs: [e.TopBuffer(defaultSignersHash)],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('signer_hash_by_epoch', e.U(2)).Value(e.TopBuffer(signersHash)),
+        e.kvs.Mapper('epoch_by_signer_hash', e.TopBuffer(signersHash)).Value(e.U(2)),
+        e.kvs.Mapper('epoch').Value(e.U(2)),
+
+        e.kvs.Mapper('revoked_epochs').UnorderedSet([e.U(1)]),
+      ],
+    });
+
+    // Owner can also revoke signers, but they are already revoked
+    await deployer.callContract({
+      callee: contract,
+      funcName: 'revokeSigners',
+      gasLimit: 5_000_000,
+      funcArgs: [e.U(1)],
+    }).assertFail({ code: 4, message: 'Signers already revoked' });
+
+    const message = e.Tuple(
+      e.Str('ethereum'),
+      e.Str('messageId'),
+      e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+      deployer,
+      e.TopBuffer(PAYLOAD_HASH),
+    );
+
+    // Revoked signers can not be used anymore
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'approveMessages',
       funcArgs: [
-        e.Str('ethereum'),
-        e.Str('messageId'),
-        e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
-        e.TopBuffer(PAYLOAD_HASH),
+        e.List(message),
+        generateProof(
+          defaultWeightedSigners, [
+            generateMessageSignature(defaultSignersHash, e.List(message)),
+            generateMessageSignature(defaultSignersHash, e.List(message), './bob.pem'),
+            null,
+          ],
+        ),
+      ],
+    }).assertFail({ code: 4, message: 'Revoked signers' });
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'approveMessagesCompact',
+      funcArgs: [
+        e.List(message),
+        generateCompactProof(1, [
+          generateMessageSignature(defaultSignersHash, e.List(message)),
+          generateMessageSignature(defaultSignersHash, e.List(message), './bob.pem'),
+          null,
+        ]),
       ],
+    }).assertFail({ code: 4, message: 'Revoked signers' });
+
+    const result = await world.query({
+      callee: contract,
+      funcName: 'revokedEpochs',
+      funcArgs: [],
     });
+    assert(result.returnData.length === 1);
     assert(result.returnData[0] === '01');
+  });
+
+  test('Revoke latest signers', async () => {
+    await deployContract();
+
+    // Operator can not revoke the latest signers
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'revokeSigners',
+      gasLimit: 5_000_000,
+      funcArgs: [e.U(1)],
+    }).assertFail({ code: 4, message: 'Can not revoke latest signers' });
+
+    await deployer.callContract({
+      callee: contract,
+      funcName: 'revokeSignersByHash',
+      gasLimit: 5_000_000,
+      funcArgs: [e.TopBuffer(defaultSignersHash)],
+    });
 
     assertAccount(await contract.getAccount(), {
-      balance: 0,
       kvs: [
         ...baseKvs(),
 
-        // Message was executed
-        e.kvs.Mapper('messages', crossChainId).Value(e.Str('1')),
+        e.kvs.Mapper('revoked_epochs').UnorderedSet([e.U(1)]),
       ],
     });
+
+    // Only the owner can unrevoke signers
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'unrevokeSigners',
+      gasLimit: 5_000_000,
+      funcArgs: [e.U(1)],
+    }).assertFail({ code: 4, message: 'Endpoint can only be called by owner' });
+
+    await deployer.callContract({
+      callee: contract,
+      funcName: 'unrevokeSigners',
+      gasLimit: 5_000_000,
+      funcArgs: [e.U(1)],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: baseKvs(),
+    });
+
+    await deployer.callContract({
+      callee: contract,
+      funcName: 'unrevokeSigners',
+      gasLimit: 5_000_000,
+      funcArgs: [e.U(1)],
+    }).assertFail({ code: 4, message: 'Signers not revoked' });
   });
 });
 
-describe('Operator', () => {
-  test('Transfer operatorship', async () => {
+describe('Pause', () => {
+  test('Pause and unpause', async () => {
     await deployContract();
 
     const otherWallet = await world.createWallet();
 
     await otherWallet.callContract({
       callee: contract,
-      funcName: 'transferOperatorship',
-      gasLimit: 5_000_000,
+      funcName: 'pause',
+      gasLimit: 5_000_000,
+      funcArgs: [],
+    }).assertFail({ code: 4, message: 'Invalid sender' });
+
+    await deployer.callContract({
+      callee: contract,
+      funcName: 'unpause',
+      gasLimit: 5_000_000,
+      funcArgs: [],
+    }).assertFail({ code: 4, message: 'Not paused' });
+
+    // Operator can pause
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'pause',
+      gasLimit: 5_000_000,
+      funcArgs: [],
+    });
+
+    assertAccount(await contract.getAccount(), {
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('paused').Value(e.Bool(true)),
+      ],
+    });
+
+    await deployer.callContract({
+      callee: contract,
+      funcName: 'pause',
+      gasLimit: 5_000_000,
+      funcArgs: [],
+    }).assertFail({ code: 4, message: 'Already paused' });
+
+    const message = e.Tuple(
+      e.Str('ethereum'),
+      e.Str('messageId'),
+      e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+      deployer,
+      e.TopBuffer(PAYLOAD_HASH),
+    );
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'approveMessages',
+      funcArgs: [
+        e.List(message),
+        generateProof(
+          defaultWeightedSigners, [
+            generateMessageSignature(defaultSignersHash, e.List(message)),
+            generateMessageSignature(defaultSignersHash, e.List(message), './bob.pem'),
+            null,
+          ],
+        ),
+      ],
+    }).assertFail({ code: 4, message: 'Gateway is paused' });
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 15_000_000,
+      funcName: 'approveMessagesCompact',
+      funcArgs: [
+        e.List(message),
+        generateCompactProof(1, [
+          generateMessageSignature(defaultSignersHash, e.List(message)),
+          generateMessageSignature(defaultSignersHash, e.List(message), './bob.pem'),
+          null,
+        ]),
+      ],
+    }).assertFail({ code: 4, message: 'Gateway is paused' });
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 10_000_000,
+      funcName: 'validateMessage',
+      funcArgs: [
+        e.Str('ethereum'),
+        e.Str('messageId'),
+        e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+        e.TopBuffer(PAYLOAD_HASH),
+      ],
+    }).assertFail({ code: 4, message: 'Gateway is paused' });
+
+    await deployer.callContract({
+      callee: contract,
+      gasLimit: 10_000_000,
+      funcName: 'callContract',
       funcArgs: [
-        otherWallet,
+        e.Str('ethereum'),
+        e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+        e.Str('payload'),
       ],
-    }).assertFail({ code: 4, message: 'Invalid sender' });
+    }).assertFail({ code: 4, message: 'Gateway is paused' });
+
+    await world.setCurrentBlockInfo({
+      timestamp: 3600,
+    });
+
+    const newWeightedSigners = e.Tuple(
+      e.List(weightedSigner(BOB_PUB_KEY, 5)),
+      e.U(5),
+      e.TopBuffer(getKeccak256Hash('nonce2')),
+    );
+    const newSignersHash = getSignersHash(
+      [
+        { signer: BOB_PUB_KEY, weight: 5 },
+      ],
+      5,
+      getKeccak256Hash('nonce2'),
+    );
 
+    // Signers can still be rotated while paused
     await deployer.callContract({
       callee: contract,
-      funcName: 'transferOperatorship',
-      gasLimit: 5_000_000,
+      gasLimit: 15_000_000,
+      funcName: 'rotateSigners',
       funcArgs: [
-        e.Addr(ADDRESS_ZERO),
+        newWeightedSigners,
+        generateProof(
+          defaultWeightedSigners, [
+            generateRotateSignersSignature(defaultSignersHash, newWeightedSigners),
+            generateRotateSignersSignature(defaultSignersHash, newWeightedSigners, './bob.pem'),
+            null,
+          ],
+        ),
       ],
-    }).assertFail({ code: 4, message: 'Invalid operator' });
+    });
 
-    // Deployer can change operatorship
+    // Owner can unpause
     await deployer.callContract({
       callee: contract,
-      funcName: 'transferOperatorship',
+      funcName: 'unpause',
       gasLimit: 5_000_000,
-      funcArgs: [
-        otherWallet,
-      ],
+      funcArgs: [],
     });
 
     assertAccount(await contract.getAccount(), {
       kvs: [
         ...baseKvs(),
 
-        e.kvs.Mapper('operator').Value(otherWallet),
-      ],
-    });
-
-    // Operator can also change operatorship
-    await otherWallet.callContract({
-      callee: contract,
-      funcName: 'transferOperatorship',
-      gasLimit: 5_000_000,
-      funcArgs: [
-        firstUser,
+        e.kvs.Mapper('signer_hash_by_epoch', e.U(2)).Value(e.TopBuffer(newSignersHash)),
+        e.kvs.Mapper('epoch_by_signer_hash', e.TopBuffer(newSignersHash)).Value(e.U(2)),
+        e.kvs.Mapper('signers_by_epoch', e.U(2)).Value(newWeightedSigners),
+        e.kvs.Mapper('epoch').Value(e.U(2)),
+        e.kvs.Mapper('last_rotation_timestamp').Value(e.U64(3600)),
       ],
     });
-
-    assertAccount(await contract.getAccount(), {
-      kvs: baseKvs(),
-    });
   });
 });
 
@@ -1197,7 +2852,7 @@ describe('View functions', () => {
         ...baseKvs(),
 
         // Manually approve message
-        e.kvs.Mapper('messages', crossChainId).Value(e.Str('1')),
+        executedMessageKvs(crossChainId),
       ],
     });
 
@@ -1225,6 +2880,193 @@ describe('View functions', () => {
     assert(result.returnData[0] === '01');
   });
 
+  test('Messages status', async () => {
+    await deployContract();
+
+    const messageHash = getMessageHash('ethereum', 'messageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', deployer);
+    const otherCrossChainId = e.Tuple(e.Str('ethereum'), e.Str('otherMessageId'));
+
+    // Mock one message approved and one executed
+    await contract.setAccount({
+      ...await contract.getAccount(),
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('messages', crossChainId).Value(messageHash),
+        // Executed before messages were pruned
+        e.kvs.Mapper('messages', otherCrossChainId).Value(e.Str('1')),
+      ],
+    });
+
+    const result = await world.query({
+      callee: contract,
+      funcName: 'messagesStatus',
+      funcArgs: [
+        crossChainId,
+        otherCrossChainId,
+        e.Tuple(e.Str('ethereum'), e.Str('nonExistentMessageId')),
+      ],
+    });
+    assert(result.returnData.length === 3);
+    assert(result.returnData[0] === '01' + messageHash.toTopHex());
+    assert(result.returnData[1] === '02');
+    assert(result.returnData[2] === '');
+  });
+
+  test('Messages approved', async () => {
+    await deployContract();
+
+    const messageHash = getMessageHash('ethereum', 'messageId', '0x4976da71bF84D750b5451B053051158EC0A4E876', deployer);
+
+    // Mock message approved
+    await contract.setAccount({
+      ...await contract.getAccount(),
+      kvs: [
+        ...baseKvs(),
+
+        e.kvs.Mapper('messages', crossChainId).Value(messageHash),
+      ],
+    });
+
+    const result = await world.query({
+      callee: contract,
+      funcName: 'areMessagesApproved',
+      funcArgs: [
+        e.Tuple(
+          e.Str('ethereum'),
+          e.Str('messageId'),
+          e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+          deployer,
+          e.TopBuffer(PAYLOAD_HASH),
+        ),
+        // Different contract address
+        e.Tuple(
+          e.Str('ethereum'),
+          e.Str('messageId'),
+          e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+          firstUser,
+          e.TopBuffer(PAYLOAD_HASH),
+        ),
+        e.Tuple(
+          e.Str('ethereum'),
+          e.Str('otherMessageId'),
+          e.Str('0x4976da71bF84D750b5451B053051158EC0A4E876'),
+          deployer,
+          e.TopBuffer(PAYLOAD_HASH),
+        ),
+      ],
+    });
+    assert(result.returnData.length === 3);
+    assert(result.returnData[0] === '01');
+    assert(result.returnData[1] === '');
+    assert(result.returnData[2] === '');
+  });
+
+  test('Signers history', async () => {
+    await deployContract();
+
+    let result = await world.query({
+      callee: contract,
+      funcName: 'currentSigners',
+      funcArgs: [],
+    });
+    assert(result.returnData[0] === defaultWeightedSigners.toTopHex());
+
+    result = await world.query({
+      callee: contract,
+      funcName: 'signersByEpoch',
+      funcArgs: [e.U(1)],
+    });
+    assert(result.returnData[0] === defaultWeightedSigners.toTopHex());
+
+    result = await world.query({
+      callee: contract,
+      funcName: 'validEpochs',
+      funcArgs: [],
+    });
+    assert(result.returnData[0] === '01');
+    assert(result.returnData[1] === '01');
+
+    // Total weight and threshold
+    result = await world.query({
+      callee: contract,
+      funcName: 'signersWeight',
+      funcArgs: [e.U(1)],
+    });
+    assert(result.returnData[0] === '12');
+    assert(result.returnData[1] === '0a');
+
+    await world.query({
+      callee: contract,
+      funcName: 'signersWeight',
+      funcArgs: [e.U(2)],
+    }).assertFail({ code: 4, message: 'Invalid epoch' });
+
+    // Mock signers being rotated before they were stored on chain
+    await contract.setAccount({
+      ...await contract.getAccount(),
+      kvs: [
+        e.kvs.Mapper('previous_signers_retention').Value(e.U(16)),
+        e.kvs.Mapper('domain_separator').Value(e.TopBuffer(DOMAIN_SEPARATOR)),
+        e.kvs.Mapper('minimum_rotation_delay').Value(e.U64(3600)),
+
+        e.kvs.Mapper('operator').Value(firstUser),
+        e.kvs.Mapper('signer_hash_by_epoch', e.U(20)).Value(e.TopBuffer(defaultSignersHash)),
+        e.kvs.Mapper('epoch_by_signer_hash', e.TopBuffer(defaultSignersHash)).Value(e.U(20)),
+        e.kvs.Mapper('epoch').Value(e.U(20)),
+      ],
+    });
+
+    result = await world.query({
+      callee: contract,
+      funcName: 'validEpochs',
+      funcArgs: [],
+    });
+    assert(result.returnData[0] === '04');
+    assert(result.returnData[1] === '14');
+
+    await world.query({
+      callee: contract,
+      funcName: 'currentSigners',
+      funcArgs: [],
+    }).assertFail({ code: 4, message: 'Invalid epoch' });
+
+    const otherWeightedSigners = e.Tuple(
+      e.List(weightedSigner(BOB_PUB_KEY, 5)),
+      e.U(5),
+      e.TopBuffer(getKeccak256Hash('nonce2')),
+    );
+
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'storeSigners',
+      gasLimit: 10_000_000,
+      funcArgs: [otherWeightedSigners],
+    }).assertFail({ code: 4, message: 'Invalid signers' });
+
+    // Anyone can store signers which match the signers hash
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'storeSigners',
+      gasLimit: 10_000_000,
+      funcArgs: [defaultWeightedSigners],
+    });
+
+    await firstUser.callContract({
+      callee: contract,
+      funcName: 'storeSigners',
+      gasLimit: 10_000_000,
+      funcArgs: [defaultWeightedSigners],
+    }).assertFail({ code: 4, message: 'Signers already stored' });
+
+    result = await world.query({
+      callee: contract,
+      funcName: 'currentSigners',
+      funcArgs: [],
+    });
+    assert(result.returnData[0] === defaultWeightedSigners.toTopHex());
+  });
+
   test('Validate proof', async () => {
     await deployContract();
 
@@ -1344,7 +3186,7 @@ describe('View functions', () => {
     }).assertFail({ code: 4, message: 'Invalid signers' });
 
     const newWeightedSigners = e.Tuple(
-      e.List(e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(5))),
+      e.List(weightedSigner(BOB_PUB_KEY, 5)),
       e.U(5),
       e.TopBuffer(getKeccak256Hash('nonce2')),
     );
@@ -1401,7 +3243,7 @@ describe('View functions', () => {
     ]);
     const spoofedWeightedSigners = e.Tuple(
       e.List(
-        e.Tuple(e.TopBuffer(ALICE_PUB_KEY), e.U(5)),
+        weightedSigner(ALICE_PUB_KEY, 5),
         e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.Buffer(spoofedWeight)), // use custom spoofed top encoded hex instead of weight
       ),
       e.U(10),
@@ -1529,7 +3371,7 @@ test('Approve validate execute external contract', async () => {
       ...baseKvs(),
 
       // Message was executed
-      e.kvs.Mapper('messages', crossChainId).Value(e.Str('1')),
+      executedMessageKvs(crossChainId),
     ],
   });
   // Other user received funds
@@ -1556,6 +3398,167 @@ test('Approve validate execute external contract', async () => {
   });
 });
 
+describe('Approve and execute', () => {
+  const approveAndExecute = (messages: Encodable[], payload: Uint8Array, executeGasLimit: number, gasLimit = 100_000_000) => deployer.callContract({
+    callee: contract,
+    gasLimit,
+    funcName: 'approveAndExecute',
+    funcArgs: [
+      e.List(...messages),
+      generateProof(
+        defaultWeightedSigners, [
+          generateMessageSignature(defaultSignersHash, e.List(...messages)),
+          generateMessageSignature(defaultSignersHash, e.List(...messages), './bob.pem'),
+          null,
+        ],
+      ),
+      e.Buffer(payload),
+      e.U64(executeGasLimit),
+    ],
+  });
+
+  const mockPingPong = async (otherUser: LSWallet) => {
+    await pingPong.setAccount({
+      ...await pingPong.getAccount(),
+      balance: 1_000,
+      kvs: [
+        e.kvs.Mapper('interchain_token_service').Value(contract),
+        e.kvs.Mapper('pingAmount').Value(e.U(1_000)),
+        e.kvs.Mapper('deadline').Value(e.U64(10)),
+        e.kvs.Mapper('activationTimestamp').Value(e.U64(0)),
+        e.kvs.Mapper('maxFunds').Value(e.Option(null)),
+
+        // User mapper
+        e.kvs.Mapper('user_address_to_id', otherUser).Value(e.U32(1)),
+        e.kvs.Mapper('user_id_to_address', e.U32(1)).Value(otherUser),
+        e.kvs.Mapper('user_count').Value(e.U32(1)),
+
+        e.kvs.Mapper('userStatus', e.U32(1)).Value(e.U8(1)),
+      ],
+    });
+  };
+
+  test('Errors', async () => {
+    await deployContract();
+    await deployPingPongInterchain(deployer, 1_000, contract);
+
+    const otherUser = await world.createWallet();
+    const payload = e.Tuple(e.Str('ping'), otherUser).toTopU8A();
+
+    const message = e.Tuple(
+      e.Str(OTHER_CHAIN_NAME),
+      e.Str(MESSAGE_ID),
+      e.Str(OTHER_CHAIN_ADDRESS),
+      pingPong,
+      e.TopBuffer(getKeccak256Hash(Buffer.from(payload))),
+    );
+    const otherMessage = e.Tuple(
+      e.Str(OTHER_CHAIN_NAME),
+      e.Str('otherMessageId'),
+      e.Str(OTHER_CHAIN_ADDRESS),
+      pingPong,
+      e.TopBuffer(getKeccak256Hash(Buffer.from(payload))),
+    );
+
+    await approveAndExecute([message, otherMessage], payload, 30_000_000)
+      .assertFail({ code: 4, message: 'Invalid messages' });
+
+    await approveAndExecute([message], Buffer.from('otherPayload'), 30_000_000)
+      .assertFail({ code: 4, message: 'Invalid payload' });
+
+    await approveAndExecute([message], payload, 80_000_000)
+      .assertFail({ code: 4, message: 'Insufficient gas for execution' });
+
+    // Mock message already executed
+    await contract.setAccount({
+      ...await contract.getAccount(),
+      kvs: [
+        ...baseKvs(),
+
+        executedMessageKvs(e.Tuple(e.Str(OTHER_CHAIN_NAME), e.Str(MESSAGE_ID))),
+      ],
+    });
+
+    await approveAndExecute([message], payload, 30_000_000)
+      .assertFail({ code: 4, message: 'Message not approved' });
+  });
+
+  test('Execute success', async () => {
+    await deployContract();
+    await deployPingPongInterchain(deployer, 1_000, contract);
+
+    const otherUser = await world.createWallet();
+    const payload = e.Tuple(e.Str('ping'), otherUser).toTopU8A();
+
+    const message = e.Tuple(
+      e.Str(OTHER_CHAIN_NAME),
+      e.Str(MESSAGE_ID),
+      e.Str(OTHER_CHAIN_ADDRESS),
+      pingPong,
+      e.TopBuffer(getKeccak256Hash(Buffer.from(payload))),
+    );
+
+    await mockPingPong(otherUser);
+
+    await world.setCurrentBlockInfo({
+      timestamp: 10,
+    });
+
+    await approveAndExecute([message], payload, 30_000_000);
+
+    assertAccount(await contract.getAccount(), {
+      balance: 0,
+      kvs: [
+        ...baseKvs(),
+
+        // Message was executed
+        executedMessageKvs(e.Tuple(e.Str(OTHER_CHAIN_NAME), e.Str(MESSAGE_ID))),
+      ],
+    });
+    // Other user received funds
+    assertAccount(await otherUser.getAccount(), {
+      balance: 1_000,
+    });
+  });
+
+  test('Execute failure keeps message approved', async () => {
+    await deployContract();
+    await deployPingPongInterchain(deployer, 1_000, contract);
+
+    const otherUser = await world.createWallet();
+    const payload = e.Tuple(e.Str('ping'), otherUser).toTopU8A();
+    const payloadHash = getKeccak256Hash(Buffer.from(payload));
+
+    const message = e.Tuple(
+      e.Str(OTHER_CHAIN_NAME),
+      e.Str(MESSAGE_ID),
+      e.Str(OTHER_CHAIN_ADDRESS),
+      pingPong,
+      e.TopBuffer(payloadHash),
+    );
+
+    await mockPingPong(otherUser);
+
+    // Ping pong execution fails since the deadline was not reached
+    await approveAndExecute([message], payload, 30_000_000);
+
+    assertAccount(await contract.getAccount(), {
+      balance: 0,
+      kvs: [
+        ...baseKvs(),
+
+        // Message is still approved
+        e.kvs.Mapper('messages', e.Tuple(e.Str(OTHER_CHAIN_NAME), e.Str(MESSAGE_ID))).Value(
+          getMessageHash(OTHER_CHAIN_NAME, MESSAGE_ID, OTHER_CHAIN_ADDRESS, pingPong, payloadHash),
+        ),
+      ],
+    });
+    assertAccount(await otherUser.getAccount(), {
+      balance: 0,
+    });
+  });
+});
+
 // Signers hash used in CosmWasm to verify that the computation there is correct.
 const multisigSignersHash = getSignersHash(
   [
@@ -1614,21 +3617,21 @@ test('Approve messages with multisig prover encoded data', async () => {
   );
 
   // 00000005 - length of signers
-  // 0139472eff6886771a982f3083da5d421f24c29181e63888228dc81ca60d69e1 00000001 01 - first signer with weight
-  // 8049d639e5a6980d1cd2392abcce41029cda74a1563523a202f09641cc2618f8 00000001 01 - second signer with weight
-  // b2a11555ce521e4944e09ab17549d85b487dcd26c84b5017a39e31a3670889ba 00000001 01 - third signer with weight
-  // ca5b4abdf9eec1f8e2d12c187d41ddd054c81979cae9e8ee9f4ecab901cac5b6 00000001 01 - fourth signer with weight
-  // ef637606f3144ee46343ba4a25c261b5c400ade88528e876f3deababa22a4449 00000001 01 - fifth signer with weight
+  // 00 00000020 0139472eff6886771a982f3083da5d421f24c29181e63888228dc81ca60d69e1 00000001 01 - first signer with weight (ed25519 key type, key length, key, weight)
+  // 00 00000020 8049d639e5a6980d1cd2392abcce41029cda74a1563523a202f09641cc2618f8 00000001 01 - second signer with weight (ed25519 key type, key length, key, weight)
+  // 00 00000020 b2a11555ce521e4944e09ab17549d85b487dcd26c84b5017a39e31a3670889ba 00000001 01 - third signer with weight (ed25519 key type, key length, key, weight)
+  // 00 00000020 ca5b4abdf9eec1f8e2d12c187d41ddd054c81979cae9e8ee9f4ecab901cac5b6 00000001 01 - fourth signer with weight (ed25519 key type, key length, key, weight)
+  // 00 00000020 ef637606f3144ee46343ba4a25c261b5c400ade88528e876f3deababa22a4449 00000001 01 - fifth signer with weight (ed25519 key type, key length, key, weight)
   // 00000001 03 - length of biguint threshold followed by 3 as hex
   // 290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563 - the nonce (keccak256 hash of Uin256 number 0, created_at date)
   // 00000005 - length of signatures
-  // 01 ${aliceSignature} - first signature encoded as a Some option
-  // 01 ${bobSignature} - second signature encoded as a Some option
-  // 01 ${carolSignature} - third signature encoded as a Some option
+  // 01 00000040 ${aliceSignature} - first signature encoded as a Some option followed by its length
+  // 01 00000040 ${bobSignature} - second signature encoded as a Some option followed by its length
+  // 01 00000040 ${carolSignature} - third signature encoded as a Some option followed by its length
   // 00 - fourth signature encoded as a None option (the fourth signer didn't specify any signature)
   // 00 - fifth signature encoded as a None option (the fifth signer didn't specify any signature)
   const proof = Buffer.from(
-    `000000050139472eff6886771a982f3083da5d421f24c29181e63888228dc81ca60d69e100000001018049d639e5a6980d1cd2392abcce41029cda74a1563523a202f09641cc2618f80000000101b2a11555ce521e4944e09ab17549d85b487dcd26c84b5017a39e31a3670889ba0000000101ca5b4abdf9eec1f8e2d12c187d41ddd054c81979cae9e8ee9f4ecab901cac5b60000000101ef637606f3144ee46343ba4a25c261b5c400ade88528e876f3deababa22a444900000001010000000103290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e5630000000501${aliceSignature}01${bobSignature}01${carolSignature}0000`,
+    `0000000500000000200139472eff6886771a982f3083da5d421f24c29181e63888228dc81ca60d69e1000000010100000000208049d639e5a6980d1cd2392abcce41029cda74a1563523a202f09641cc2618f800000001010000000020b2a11555ce521e4944e09ab17549d85b487dcd26c84b5017a39e31a3670889ba00000001010000000020ca5b4abdf9eec1f8e2d12c187d41ddd054c81979cae9e8ee9f4ecab901cac5b600000001010000000020ef637606f3144ee46343ba4a25c261b5c400ade88528e876f3deababa22a444900000001010000000103290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563000000050100000040${aliceSignature}0100000040${bobSignature}0100000040${carolSignature}0000`,
     'hex',
   );
 
@@ -1664,9 +3667,9 @@ test('Approve messages with multisig prover encoded data', async () => {
 test('Rotate signers with multisig prover encoded data', async () => {
   const newWeightedSigners = e.Tuple(
     e.List(
-      e.Tuple(e.TopBuffer(ALICE_PUB_KEY), e.U(1)),
-      e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(1)),
-      e.Tuple(e.TopBuffer(CAROL_PUB_KEY), e.U(1)),
+      weightedSigner(ALICE_PUB_KEY, 1),
+      weightedSigner(BOB_PUB_KEY, 1),
+      weightedSigner(CAROL_PUB_KEY, 1),
     ),
     e.U(3),
     e.TopBuffer('290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563'),
@@ -1700,35 +3703,35 @@ test('Rotate signers with multisig prover encoded data', async () => {
   });
 
   // 00000003 - length of new signers
-  // 0139472eff6886771a982f3083da5d421f24c29181e63888228dc81ca60d69e1 - first new signer
+  // 00 00000020 0139472eff6886771a982f3083da5d421f24c29181e63888228dc81ca60d69e1 - first new signer (ed25519 key type, key length, key)
   // 00000001 01 - length of biguint weight followed by 1 as hex
-  // 8049d639e5a6980d1cd2392abcce41029cda74a1563523a202f09641cc2618f8 - second new signer
+  // 00 00000020 8049d639e5a6980d1cd2392abcce41029cda74a1563523a202f09641cc2618f8 - second new signer (ed25519 key type, key length, key)
   // 00000001 01 - length of biguint weight followed by 1 as hex
-  // b2a11555ce521e4944e09ab17549d85b487dcd26c84b5017a39e31a3670889ba - third new signer
+  // 00 00000020 b2a11555ce521e4944e09ab17549d85b487dcd26c84b5017a39e31a3670889ba - third new signer (ed25519 key type, key length, key)
   // 00000001 01 - length of biguint weight followed by 1 as hex
   // 00000001 03 - length of biguint threshold followed by 3 as hex
   // 290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563 - the nonce (mock created at number as uint256)
   const newSigners = Buffer.from(
-    '000000030139472eff6886771a982f3083da5d421f24c29181e63888228dc81ca60d69e100000001018049d639e5a6980d1cd2392abcce41029cda74a1563523a202f09641cc2618f80000000101b2a11555ce521e4944e09ab17549d85b487dcd26c84b5017a39e31a3670889ba00000001010000000103290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563',
+    '0000000300000000200139472eff6886771a982f3083da5d421f24c29181e63888228dc81ca60d69e1000000010100000000208049d639e5a6980d1cd2392abcce41029cda74a1563523a202f09641cc2618f800000001010000000020b2a11555ce521e4944e09ab17549d85b487dcd26c84b5017a39e31a3670889ba00000001010000000103290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563',
     'hex',
   );
 
   // 00000005 - length of signers
-  // 0139472eff6886771a982f3083da5d421f24c29181e63888228dc81ca60d69e1 00000001 01 - first signer with weight
-  // 8049d639e5a6980d1cd2392abcce41029cda74a1563523a202f09641cc2618f8 00000001 01 - second signer with weight
-  // b2a11555ce521e4944e09ab17549d85b487dcd26c84b5017a39e31a3670889ba 00000001 01 - third signer with weight
-  // ca5b4abdf9eec1f8e2d12c187d41ddd054c81979cae9e8ee9f4ecab901cac5b6 00000001 01 - fourth signer with weight
-  // ef637606f3144ee46343ba4a25c261b5c400ade88528e876f3deababa22a4449 00000001 01 - fifth signer with weight
+  // 00 00000020 0139472eff6886771a982f3083da5d421f24c29181e63888228dc81ca60d69e1 00000001 01 - first signer with weight (ed25519 key type, key length, key, weight)
+  // 00 00000020 8049d639e5a6980d1cd2392abcce41029cda74a1563523a202f09641cc2618f8 00000001 01 - second signer with weight (ed25519 key type, key length, key, weight)
+  // 00 00000020 b2a11555ce521e4944e09ab17549d85b487dcd26c84b5017a39e31a3670889ba 00000001 01 - third signer with weight (ed25519 key type, key length, key, weight)
+  // 00 00000020 ca5b4abdf9eec1f8e2d12c187d41ddd054c81979cae9e8ee9f4ecab901cac5b6 00000001 01 - fourth signer with weight (ed25519 key type, key length, key, weight)
+  // 00 00000020 ef637606f3144ee46343ba4a25c261b5c400ade88528e876f3deababa22a4449 00000001 01 - fifth signer with weight (ed25519 key type, key length, key, weight)
   // 00000001 03 - length of biguint threshold followed by 3 as hex
   // 290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563 - the nonce (mock created at number as uint256)
   // 00000005 - length of signatures
-  // 01 ${aliceSignature} - first signature encoded as a Some option
-  // 01 ${bobSignature} - second signature encoded as a Some option
-  // 01 ${carolSignature} - third signature encoded as a Some option
+  // 01 00000040 ${aliceSignature} - first signature encoded as a Some option followed by its length
+  // 01 00000040 ${bobSignature} - second signature encoded as a Some option followed by its length
+  // 01 00000040 ${carolSignature} - third signature encoded as a Some option followed by its length
   // 00 - fourth signature encoded as a None option (the fourth signer didn't specify any signature)
   // 00 - fifth signature encoded as a None option (the fifth signer didn't specify any signature)
   const proof = Buffer.from(
-    `000000050139472eff6886771a982f3083da5d421f24c29181e63888228dc81ca60d69e100000001018049d639e5a6980d1cd2392abcce41029cda74a1563523a202f09641cc2618f80000000101b2a11555ce521e4944e09ab17549d85b487dcd26c84b5017a39e31a3670889ba0000000101ca5b4abdf9eec1f8e2d12c187d41ddd054c81979cae9e8ee9f4ecab901cac5b60000000101ef637606f3144ee46343ba4a25c261b5c400ade88528e876f3deababa22a444900000001010000000103290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e5630000000501${aliceSignature}01${bobSignature}01${carolSignature}0000`,
+    `0000000500000000200139472eff6886771a982f3083da5d421f24c29181e63888228dc81ca60d69e1000000010100000000208049d639e5a6980d1cd2392abcce41029cda74a1563523a202f09641cc2618f800000001010000000020b2a11555ce521e4944e09ab17549d85b487dcd26c84b5017a39e31a3670889ba00000001010000000020ca5b4abdf9eec1f8e2d12c187d41ddd054c81979cae9e8ee9f4ecab901cac5b600000001010000000020ef637606f3144ee46343ba4a25c261b5c400ade88528e876f3deababa22a444900000001010000000103290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563000000050100000040${aliceSignature}0100000040${bobSignature}0100000040${carolSignature}0000`,
     'hex',
   );
 
@@ -1759,6 +3762,7 @@ test('Rotate signers with multisig prover encoded data', async () => {
 
       e.kvs.Mapper('signer_hash_by_epoch', e.U(2)).Value(e.TopBuffer(newSignersHash)),
       e.kvs.Mapper('epoch_by_signer_hash', e.TopBuffer(newSignersHash)).Value(e.U(2)),
+      e.kvs.Mapper('signers_by_epoch', e.U(2)).Value(newWeightedSigners),
     ],
   });
 });
@@ -1,12 +1,16 @@
 import fs from 'fs';
 import { UserSecretKey } from '@multiversx/sdk-wallet/out';
 import createKeccakHash from 'keccak';
+import { SigningKey } from 'ethers';
 import { e, Encodable } from 'xsuite';
 
 export const ALICE_PUB_KEY = '0139472eff6886771a982f3083da5d421f24c29181e63888228dc81ca60d69e1';
 export const BOB_PUB_KEY = '8049d639e5a6980d1cd2392abcce41029cda74a1563523a202f09641cc2618f8';
 export const CAROL_PUB_KEY = 'b2a11555ce521e4944e09ab17549d85b487dcd26c84b5017a39e31a3670889ba';
 
+export const SECP256K1_PRIVATE_KEY = '0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318';
+export const SECP256K1_PUB_KEY = new SigningKey(SECP256K1_PRIVATE_KEY).compressedPublicKey.slice(2);
+
 export const TOKEN_SALT: string = '91b44915de5f5bb438be952d4cda1bcc08829495e8704e40751dcee97aa83886';
 export const TOKEN_SALT2: string = '8be14915de5f5bb438be952d4cda1bcc08829495e8704e40751dcee97aa89854';
 export const TOKEN_IDENTIFIER: string = 'WEGLD-123456';
@@ -36,10 +40,14 @@ export const PAYLOAD_HASH: string = '07b8e6f7ea72578a764983050201bba8fda552f6510
 
 export const MULTIVERSX_SIGNED_MESSAGE_PREFIX = '\x19MultiversX Signed Message:\n';
 
-export const getAuthMessageHash = (signersHash: Buffer, dataHash: Buffer): string => {
+export const getAuthMessageHash = (
+  signersHash: Buffer,
+  dataHash: Buffer,
+  domainSeparator: string = DOMAIN_SEPARATOR
+): string => {
   const messageHashData = Buffer.concat([
     Buffer.from(MULTIVERSX_SIGNED_MESSAGE_PREFIX),
-    Buffer.from(DOMAIN_SEPARATOR, 'hex'),
+    Buffer.from(domainSeparator, 'hex'),
     signersHash,
     dataHash,
   ]);
@@ -47,7 +55,12 @@ export const getAuthMessageHash = (signersHash: Buffer, dataHash: Buffer): strin
   return createKeccakHash('keccak256').update(messageHashData).digest('hex');
 };
 
-export const generateMessageSignature = (signersHash: Buffer, data: Encodable, signerPem = './alice.pem'): Buffer => {
+export const generateMessageSignature = (
+  signersHash: Buffer,
+  data: Encodable,
+  signerPem = './alice.pem',
+  domainSeparator: string = DOMAIN_SEPARATOR
+): Buffer => {
   const dataHash = getKeccak256Hash(
     Buffer.concat([
       Buffer.from('00', 'hex'), // ApproveMessages command type,
@@ -55,7 +68,7 @@ export const generateMessageSignature = (signersHash: Buffer, data: Encodable, s
     ])
   );
 
-  const messageHashToSign = getAuthMessageHash(signersHash, Buffer.from(dataHash, 'hex'));
+  const messageHashToSign = getAuthMessageHash(signersHash, Buffer.from(dataHash, 'hex'), domainSeparator);
 
   const file = fs.readFileSync(signerPem).toString();
   const privateKey = UserSecretKey.fromPem(file);
@@ -63,6 +76,26 @@ export const generateMessageSignature = (signersHash: Buffer, data: Encodable, s
   return privateKey.sign(Buffer.from(messageHashToSign, 'hex'));
 };
 
+// Signs the message hash as `r || s`, as expected by the Gateway for secp256k1 signers
+export const generateSecp256k1MessageSignature = (
+  signersHash: Buffer,
+  data: Encodable,
+  privateKey: string = SECP256K1_PRIVATE_KEY
+): Buffer => {
+  const dataHash = getKeccak256Hash(
+    Buffer.concat([
+      Buffer.from('00', 'hex'), // ApproveMessages command type,
+      data.toTopU8A(),
+    ])
+  );
+
+  const messageHashToSign = getAuthMessageHash(signersHash, Buffer.from(dataHash, 'hex'));
+
+  const signature = new SigningKey(privateKey).sign(`0x${messageHashToSign}`);
+
+  return Buffer.from(signature.r.slice(2) + signature.s.slice(2), 'hex');
+};
+
 export const generateRotateSignersSignature = (
   signersHash: Buffer,
   data: Encodable,
@@ -83,6 +116,46 @@ export const generateRotateSignersSignature = (
   return privateKey.sign(Buffer.from(messageHashToSign, 'hex'));
 };
 
+export const generateCancelRotateSignersSignature = (
+  signersHash: Buffer,
+  data: Encodable,
+  signerPem = './alice.pem'
+): Buffer => {
+  const dataHash = getKeccak256Hash(
+    Buffer.concat([
+      Buffer.from('02', 'hex'), // CancelRotateSigners command type,
+      data.toTopU8A(),
+    ])
+  );
+
+  const messageHashToSign = getAuthMessageHash(signersHash, Buffer.from(dataHash, 'hex'));
+
+  const file = fs.readFileSync(signerPem).toString();
+  const privateKey = UserSecretKey.fromPem(file);
+
+  return privateKey.sign(Buffer.from(messageHashToSign, 'hex'));
+};
+
+export const generateRotateDomainSeparatorSignature = (
+  signersHash: Buffer,
+  data: Encodable,
+  signerPem = './alice.pem'
+): Buffer => {
+  const dataHash = getKeccak256Hash(
+    Buffer.concat([
+      Buffer.from('03', 'hex'), // RotateDomainSeparator command type,
+      data.toTopU8A(),
+    ])
+  );
+
+  const messageHashToSign = getAuthMessageHash(signersHash, Buffer.from(dataHash, 'hex'));
+
+  const file = fs.readFileSync(signerPem).toString();
+  const privateKey = UserSecretKey.fromPem(file);
+
+  return privateKey.sign(Buffer.from(messageHashToSign, 'hex'));
+};
+
 export const getMessageHash = (
   sourceChain: string,
   messageId: string,
@@ -100,18 +173,135 @@ export const getMessageHash = (
   return e.TopBuffer(getKeccak256Hash(messageData));
 };
 
-export const getSignersHash = (signers: { signer: string; weight: number }[], threshold: number, nonce: string) => {
+// Executed messages are stored by the Gateway as a marker under the hash of their cross chain id
+export const executedMessageKvs = (crossChainId: Encodable) => {
+  return e.kvs
+    .Mapper('executed_messages', e.TopBuffer(getKeccak256Hash(Buffer.from(crossChainId.toTopU8A()))))
+    .Value(e.Bool(true));
+};
+
+// Gas payment recorded by the Gas Service ledger
+export const gasPaymentKvs = (
+  paymentId: number,
+  token: string,
+  amount: number | bigint,
+  refundAddress: Encodable,
+  refunded: number | bigint = 0,
+  settled: boolean = false,
+  express: boolean = false
+) => {
+  return multiTokenGasPaymentKvs(
+    paymentId,
+    [{ id: token, amount }],
+    refundAddress,
+    settled ? [refunded] : [],
+    settled,
+    express
+  );
+};
+
+// Gas payment made in several tokens, refunded amounts being in the same order as the tokens
+export const multiTokenGasPaymentKvs = (
+  paymentId: number,
+  tokens: { id: string; amount: number | bigint }[],
+  refundAddress: Encodable,
+  refunded: (number | bigint)[] = [],
+  settled: boolean = false,
+  express: boolean = false
+) => {
+  return e.kvs
+    .Mapper('payments', e.U64(paymentId))
+    .Value(
+      e.Tuple(
+        e.List(...tokens.map(({ id, amount }) => e.Tuple(e.Str(id), e.U(amount)))),
+        refundAddress,
+        e.List(...refunded.map((amount) => e.U(amount))),
+        e.Bool(settled),
+        e.Bool(express)
+      )
+    );
+};
+
+// Cumulative totals kept by the Gas Service for a token, totals which are 0 not being stored
+export const gasTotalsKvs = (
+  token: string,
+  paid: number | bigint,
+  refunded: number | bigint = 0,
+  collected: number | bigint = 0,
+  reimbursed: number | bigint = 0
+) => {
+  return [
+    ['total_gas_paid', paid],
+    ['total_gas_refunded', refunded],
+    ['total_fees_collected', collected],
+    ['total_express_reimbursed', reimbursed],
+  ]
+    .filter(([, amount]) => BigInt(amount) !== 0n)
+    .map(([name, amount]) => e.kvs.Mapper(name as string, e.Str(token)).Value(e.U(amount as number | bigint)));
+};
+
+// Cumulative totals kept by the Gas Service for payments made with the pay gas endpoints
+export const chainGasTotalsKvs = (
+  destinationChain: string,
+  token: string,
+  paid: number | bigint,
+  refunded: number | bigint = 0
+) => {
+  return [
+    ['total_gas_paid_for_chain', paid],
+    ['total_gas_refunded_for_chain', refunded],
+  ]
+    .filter(([, amount]) => BigInt(amount) !== 0n)
+    .map(([name, amount]) =>
+      e.kvs.Mapper(name as string, e.Str(destinationChain), e.Str(token)).Value(e.U(amount as number | bigint))
+    );
+};
+
+export const paymentDestinationChainKvs = (paymentId: number, destinationChain: string) => {
+  return e.kvs.Mapper('payment_destination_chain', e.U64(paymentId)).Value(e.Str(destinationChain));
+};
+
+export const SIGNER_KEY_TYPE_ED25519 = 0;
+export const SIGNER_KEY_TYPE_SECP256K1 = 1;
+
+export const weightedSigner = (signer: string, weight: number, keyType: number = SIGNER_KEY_TYPE_ED25519): Encodable => {
+  return e.Tuple(e.U8(keyType), e.Buffer(signer), e.U(weight));
+};
+
+export const getSignersHash = (
+  signers: { signer: string; weight: number; keyType?: number }[],
+  threshold: number,
+  nonce: string
+) => {
   let signersLengthHex = numberToHex(signers.length, 4);
 
   let thresholdHex = numberToHex(threshold);
 
+  // Sets made only of ed25519 signers are hashed without key types, as before secp256k1 signers were supported
+  let legacyEncoding = signers.every((signer) => (signer.keyType ?? SIGNER_KEY_TYPE_ED25519) === SIGNER_KEY_TYPE_ED25519);
+
   let data = Buffer.concat([
     Buffer.from(signersLengthHex, 'hex'),
     ...signers.map((signer) => {
+      if (legacyEncoding) {
+        let weightHex = numberToHex(signer.weight);
+        let weightHexLengthHex = numberToHex(weightHex.length / 2, 4);
+
+        return Buffer.concat([
+          Buffer.from(signer.signer, 'hex'),
+          Buffer.from(weightHexLengthHex, 'hex'),
+          Buffer.from(weightHex, 'hex'),
+        ]);
+      }
+
+      let keyTypeHex = numberToHex(signer.keyType ?? SIGNER_KEY_TYPE_ED25519, 1);
+      let signerLengthHex = numberToHex(signer.signer.length / 2, 4);
       let weightHex = numberToHex(signer.weight);
       let weightHexLengthHex = numberToHex(weightHex.length / 2, 4);
 
       return Buffer.concat([
+        Buffer.from(keyTypeHex, 'hex'),
+        Buffer.from(signerLengthHex, 'hex'),
         Buffer.from(signer.signer, 'hex'),
         Buffer.from(weightHexLengthHex, 'hex'),
         Buffer.from(weightHex, 'hex'),
@@ -143,12 +333,33 @@ export const generateProof = (weightedSigners: Encodable, signatures: (Buffer |
     weightedSigners,
     e.List(
       ...signatures.map((signature) => {
-        return e.Option(signature === null ? null : e.TopBuffer(signature));
+        return e.Option(signature === null ? null : e.Buffer(signature));
       })
     )
   );
 };
 
+// The bitmap has one bit for each signer, with the least significant bit of the first byte representing the first signer
+export const generateCompactProof = (epoch: number, signatures: (Buffer | null)[]): Encodable => {
+  const bitmap = Buffer.alloc(Math.ceil(signatures.length / 8));
+
+  signatures.forEach((signature, index) => {
+    if (signature !== null) {
+      bitmap[Math.floor(index / 8)] |= 1 << index % 8;
+    }
+  });
+
+  return e.Tuple(
+    e.U(epoch),
+    e.Buffer(bitmap),
+    e.List(
+      ...signatures
+        .filter((signature): signature is Buffer => signature !== null)
+        .map((signature) => e.Buffer(signature))
+    )
+  );
+};
+
 export const getKeccak256Hash = (payload: string | Buffer) => {
   return createKeccakHash('keccak256').update(Buffer.from(payload)).digest('hex');
 };
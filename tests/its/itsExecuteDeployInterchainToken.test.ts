@@ -1,6 +1,7 @@
 import { afterEach, beforeEach, test } from 'vitest';
 import { assertAccount, e, LSWallet, LSWorld } from 'xsuite';
 import {
+  executedMessageKvs,
   INTERCHAIN_TOKEN_ID,
   MESSAGE_ID,
   OTHER_CHAIN_NAME,
@@ -183,7 +184,7 @@ test('Only issue esdt', async () => {
 
   // Gateway message was marked as executed
   assertAccount(await gateway.getAccount(), {
-    kvs: [...baseGatewayKvs(deployer), e.kvs.Mapper('messages', crossChainId).Value(e.Str('1'))],
+    kvs: [...baseGatewayKvs(deployer), executedMessageKvs(crossChainId)],
   });
 });
 
@@ -1,6 +1,7 @@
 import { afterEach, beforeEach, test } from 'vitest';
 import { assertAccount, e, LSWallet, LSWorld } from 'xsuite';
 import {
+  executedMessageKvs,
   INTERCHAIN_TOKEN_ID,
   MESSAGE_ID,
   OTHER_CHAIN_ADDRESS,
@@ -139,7 +140,7 @@ test('Transfer mint burn', async () => {
 
   // Gateway message was marked as executed
   assertAccount(await gateway.getAccount(), {
-    kvs: [...baseGatewayKvs(deployer), e.kvs.Mapper('messages', crossChainId).Value(e.Str('1'))],
+    kvs: [...baseGatewayKvs(deployer), executedMessageKvs(crossChainId)],
   });
 });
 
@@ -175,7 +176,7 @@ test('Transfer lock unlock', async () => {
 
   // Gateway message was marked as executed
   assertAccount(await gateway.getAccount(), {
-    kvs: [...baseGatewayKvs(deployer), e.kvs.Mapper('messages', crossChainId).Value(e.Str('1'))],
+    kvs: [...baseGatewayKvs(deployer), executedMessageKvs(crossChainId)],
   });
 });
 
@@ -1,6 +1,13 @@
 import { afterEach, beforeEach, test } from 'vitest';
 import { assertAccount, e, LSWallet, LSWorld } from 'xsuite';
-import { MESSAGE_ID, OTHER_CHAIN_ADDRESS, OTHER_CHAIN_NAME, TOKEN_IDENTIFIER, TOKEN_IDENTIFIER2 } from '../helpers';
+import {
+  executedMessageKvs,
+  MESSAGE_ID,
+  OTHER_CHAIN_ADDRESS,
+  OTHER_CHAIN_NAME,
+  TOKEN_IDENTIFIER,
+  TOKEN_IDENTIFIER2,
+} from '../helpers';
 import { Buffer } from 'buffer';
 import {
   baseGatewayKvs,
@@ -159,7 +166,7 @@ test('Transfer with data', async () => {
 
   // Gateway message was marked as executed
   assertAccount(await gateway.getAccount(), {
-    kvs: [...baseGatewayKvs(deployer), e.kvs.Mapper('messages', crossChainId).Value(e.Str('1'))],
+    kvs: [...baseGatewayKvs(deployer), executedMessageKvs(crossChainId)],
   });
 });
 
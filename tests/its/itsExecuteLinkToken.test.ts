@@ -2,6 +2,7 @@ import { afterEach, beforeEach, test } from 'vitest';
 import { assertAccount, e, LSWallet, LSWorld } from 'xsuite';
 import {
   ADDRESS_ZERO,
+  executedMessageKvs,
   INTERCHAIN_TOKEN_ID,
   MESSAGE_ID,
   OTHER_CHAIN_ADDRESS,
@@ -139,7 +140,7 @@ test('Execute no operator', async () => {
 
   // Gateway message was marked as executed
   assertAccount(await gateway.getAccount(), {
-    kvs: [...baseGatewayKvs(deployer), e.kvs.Mapper('messages', crossChainId).Value(e.Str('1'))],
+    kvs: [...baseGatewayKvs(deployer), executedMessageKvs(crossChainId)],
   });
 });
 
@@ -179,7 +180,7 @@ test('Execute with operator', async () => {
 
   // Gateway message was marked as executed
   assertAccount(await gateway.getAccount(), {
-    kvs: [...baseGatewayKvs(deployer), e.kvs.Mapper('messages', crossChainId).Value(e.Str('1'))],
+    kvs: [...baseGatewayKvs(deployer), executedMessageKvs(crossChainId)],
   });
 });
 
@@ -220,7 +221,7 @@ test('Execute egld', async () => {
 
   // Gateway message was marked as executed
   assertAccount(await gateway.getAccount(), {
-    kvs: [...baseGatewayKvs(deployer), e.kvs.Mapper('messages', crossChainId).Value(e.Str('1'))],
+    kvs: [...baseGatewayKvs(deployer), executedMessageKvs(crossChainId)],
   });
 });
 
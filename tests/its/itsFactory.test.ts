@@ -4,11 +4,15 @@ import {
   ADDRESS_ZERO,
   CANONICAL_INTERCHAIN_TOKEN_ID,
   CHAIN_NAME,
+  chainGasTotalsKvs,
+  gasPaymentKvs,
+  gasTotalsKvs,
   getKeccak256Hash,
   INTERCHAIN_TOKEN_ID,
   OTHER_CHAIN_ADDRESS,
   OTHER_CHAIN_NAME,
   OTHER_CHAIN_TOKEN_ADDRESS,
+  paymentDestinationChainKvs,
   TOKEN_IDENTIFIER,
   TOKEN_IDENTIFIER2,
   TOKEN_MANAGER_ADDRESS,
@@ -756,7 +760,14 @@ describe('Deploy remote interchain token', () => {
     const gasServiceKvs = await gasService.getAccount();
     assertAccount(gasServiceKvs, {
       balance: 100_000_000n,
-      kvs: [e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString()))],
+      kvs: [
+        e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+        e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+        gasPaymentKvs(1, 'EGLD', 100_000_000n, e.Addr(user.toString())),
+        paymentDestinationChainKvs(1, 'axelar'),
+        gasTotalsKvs('EGLD', 100_000_000n),
+        chainGasTotalsKvs('axelar', 'EGLD', 100_000_000n),
+      ],
     });
 
     // There are events emitted for the Gateway contract, but there is no way to test those currently...
@@ -1154,7 +1165,14 @@ describe('Deploy remote canonical interchain token', () => {
     // Assert gas was paid for cross chain call
     assertAccount(await gasService.getAccount(), {
       balance: 100_000_000n,
-      kvs: [e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString()))],
+      kvs: [
+        e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+        e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+        gasPaymentKvs(1, 'EGLD', 100_000_000n, e.Addr(user.toString())),
+        paymentDestinationChainKvs(1, 'axelar'),
+        gasTotalsKvs('EGLD', 100_000_000n),
+        chainGasTotalsKvs('axelar', 'EGLD', 100_000_000n),
+      ],
     });
 
     // There are events emitted for the Gateway contract, but there is no way to test those currently...
@@ -1451,7 +1469,14 @@ describe('Link token', () => {
     kvs = await gasService.getAccount();
     assertAccount(kvs, {
       balance: 100_000,
-      kvs: [e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString()))],
+      kvs: [
+        e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+        e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+        gasPaymentKvs(1, 'EGLD', 100_000, e.Addr(user.toString())),
+        paymentDestinationChainKvs(1, 'axelar'),
+        gasTotalsKvs('EGLD', 100_000),
+        chainGasTotalsKvs('axelar', 'EGLD', 100_000),
+      ],
     });
 
     // There are events emitted for the Gateway contract, but there is no way to test those currently...
@@ -1486,7 +1511,16 @@ describe('Link token', () => {
     kvs = await gasService.getAccount();
     assertAccount(kvs, {
       balance: 150_000,
-      kvs: [e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString()))],
+      kvs: [
+        e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+        e.kvs.Mapper('last_payment_id').Value(e.U64(2)),
+        gasPaymentKvs(1, 'EGLD', 100_000, e.Addr(user.toString())),
+        gasPaymentKvs(2, 'EGLD', 50_000, e.Addr(otherUser.toString())),
+        paymentDestinationChainKvs(1, 'axelar'),
+        paymentDestinationChainKvs(2, 'axelar'),
+        gasTotalsKvs('EGLD', 150_000),
+        chainGasTotalsKvs('axelar', 'EGLD', 150_000),
+      ],
     });
   });
 
@@ -1537,7 +1571,14 @@ describe('Link token', () => {
     kvs = await gasService.getAccount();
     assertAccount(kvs, {
       balance: 100_000,
-      kvs: [e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString()))],
+      kvs: [
+        e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+        e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+        gasPaymentKvs(1, 'EGLD', 100_000, e.Addr(user.toString())),
+        paymentDestinationChainKvs(1, 'axelar'),
+        gasTotalsKvs('EGLD', 100_000),
+        chainGasTotalsKvs('axelar', 'EGLD', 100_000),
+      ],
     });
 
     // There are events emitted for the Gateway contract, but there is no way to test those currently...
@@ -1,9 +1,13 @@
 import { afterEach, beforeEach, describe, test } from 'vitest';
 import { assertAccount, e, LSWallet, LSWorld } from 'xsuite';
 import {
+  chainGasTotalsKvs,
+  gasPaymentKvs,
+  gasTotalsKvs,
   INTERCHAIN_TOKEN_ID,
   OTHER_CHAIN_ADDRESS,
   OTHER_CHAIN_NAME,
+  paymentDestinationChainKvs,
   TOKEN_IDENTIFIER,
   TOKEN_IDENTIFIER2,
   TOKEN_IDENTIFIER_EGLD,
@@ -90,7 +94,7 @@ describe('Interchain transfer', () => {
     let kvs = await gasService.getAccount();
     assertAccount(kvs, {
       balance: 0,
-      kvs: [e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString()))],
+      kvs: [e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000))],
     });
 
     let tokenManagerKvs = await tokenManager.getAccount();
@@ -127,7 +131,7 @@ describe('Interchain transfer', () => {
     const kvs = await gasService.getAccount();
     assertAccount(kvs, {
       balance: 0,
-      kvs: [e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString()))],
+      kvs: [e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000))],
     });
 
     const tokenManagerKvs = await tokenManager.getAccount();
@@ -421,7 +425,14 @@ describe('Interchain transfer', () => {
     let kvs = await gasService.getAccount();
     assertAccount(kvs, {
       balance: 20,
-      kvs: [e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString()))],
+      kvs: [
+        e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+        e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+        gasPaymentKvs(1, 'EGLD', 20, e.Addr(user.toString())),
+        paymentDestinationChainKvs(1, 'axelar'),
+        gasTotalsKvs('EGLD', 20),
+        chainGasTotalsKvs('axelar', 'EGLD', 20),
+      ],
     });
 
     let tokenManagerKvs = await tokenManager.getAccount();
@@ -455,7 +466,14 @@ describe('Interchain transfer', () => {
     let kvs = await gasService.getAccount();
     assertAccount(kvs, {
       balance: 100,
-      kvs: [e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString()))],
+      kvs: [
+        e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000)),
+        e.kvs.Mapper('last_payment_id').Value(e.U64(1)),
+        gasPaymentKvs(1, 'EGLD', 100, e.Addr(user.toString())),
+        paymentDestinationChainKvs(1, 'axelar'),
+        gasTotalsKvs('EGLD', 100),
+        chainGasTotalsKvs('axelar', 'EGLD', 100),
+      ],
     });
 
     let tokenManagerKvs = await tokenManager.getAccount();
@@ -486,7 +504,7 @@ describe('Interchain transfer', () => {
     let kvs = await gasService.getAccount();
     assertAccount(kvs, {
       balance: 0,
-      kvs: [e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString()))],
+      kvs: [e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000))],
     });
 
     let tokenManagerKvs = await tokenManager.getAccount();
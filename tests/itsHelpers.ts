@@ -10,6 +10,7 @@ import {
   getKeccak256Hash,
   getMessageHash,
   getSignersHash,
+  weightedSigner,
   INTERCHAIN_TOKEN_ID,
   MESSAGE_ID,
   OTHER_CHAIN_NAME,
@@ -54,9 +55,9 @@ export let pingPong: LSContract;
 
 export const defaultWeightedSigners = e.Tuple(
   e.List(
-    e.Tuple(e.TopBuffer(ALICE_PUB_KEY), e.U(5)),
-    e.Tuple(e.TopBuffer(BOB_PUB_KEY), e.U(6)),
-    e.Tuple(e.TopBuffer(CAROL_PUB_KEY), e.U(7))
+    weightedSigner(ALICE_PUB_KEY, 5),
+    weightedSigner(BOB_PUB_KEY, 6),
+    weightedSigner(CAROL_PUB_KEY, 7)
   ),
   e.U(10),
   e.TopBuffer(getKeccak256Hash('nonce1'))
@@ -81,6 +82,7 @@ export const baseGatewayKvs = (operator: Wallet) => {
     e.kvs.Mapper('operator').Value(operator),
     e.kvs.Mapper('signer_hash_by_epoch', e.U(1)).Value(e.TopBuffer(defaultSignersHash)),
     e.kvs.Mapper('epoch_by_signer_hash', e.TopBuffer(defaultSignersHash)).Value(e.U(1)),
+    e.kvs.Mapper('signers_by_epoch', e.U(1)).Value(defaultWeightedSigners),
     e.kvs.Mapper('epoch').Value(e.U(1)),
   ];
 };
@@ -111,7 +113,7 @@ export const deployGasService = async (deployer: LSWallet, collector: LSWallet)
   const kvs = await gasService.getAccount();
   assertAccount(kvs, {
     balance: 0n,
-    kvs: [e.kvs.Mapper('gas_collector').Value(e.Addr(collector.toString()))],
+    kvs: [e.kvs.Mapper('account_roles', e.Addr(collector.toString())).Value(e.U32(0b00011000))],
   });
 };
 